@@ -1,23 +1,47 @@
 use std::collections::HashMap;
+use rand::Rng;
 use rand::prelude::SliceRandom;
+use rand::rngs::StdRng;
 use crate::{Game, PlayMarkers};
+use crate::move_scores::get_max_key;
+use crate::player::Player;
 
-pub fn get_max_key<K, V>(a_hash_map: &HashMap<K, V>) -> Option<&K> where V: Ord,
-{
-    a_hash_map
-        .iter()
-        .max_by(|a, b| a.1.cmp(&b.1))
-        .map(|(k, _v)| k)
+pub struct AiPlayer {
+    rng: StdRng,
+}
+
+impl AiPlayer {
+    pub fn new(rng: StdRng) -> Self {
+        Self {
+            rng,
+        }
+    }
+}
+
+impl Player for AiPlayer {
+    fn choose_move(&mut self, game: &Game) -> u8 {
+        get_best_move_with_rng(game, &mut self.rng)
+    }
 }
 
 pub fn get_best_move(game: &Game) -> u8 {
+    get_best_move_with_rng(game, &mut rand::thread_rng())
+}
+
+// The search has no depth limit, so it only finishes in reasonable time on the classic board.
+pub fn supports_size(size: u8) -> bool {
+    size <= 3
+}
+
+pub fn get_best_move_with_rng<R: Rng>(game: &Game, rng: &mut R) -> u8 {
+    assert!(supports_size(game.get_board().get_size()), "The exhaustive ai only plays on boards up to 3 x 3.");
     let active_marker = game.get_active_marker();
     let best_move: u8;
     let mut moves = game.get_available_plays();
     let mut scores: HashMap<u8, i32> = HashMap::new();
     loop {
-        if moves.len() == 9 {
-            best_move = *moves.choose(&mut rand::thread_rng()).unwrap();
+        if moves.len() == game.get_board().get_space_count() as usize {
+            best_move = *moves.choose(rng).unwrap();
             break;
         } else if moves.is_empty() {
             let max_key = get_max_key(&scores);
@@ -26,7 +50,7 @@ pub fn get_best_move(game: &Game) -> u8 {
         } else {
             let play = moves.pop().unwrap();
             let mut game_copy: Game = game.copy();
-            game_copy.play(play);
+            game_copy.play(play).unwrap();
             let score = minimax(&game_copy, active_marker, 0, false);
             scores.insert(play, score);
         };
@@ -43,12 +67,12 @@ fn minimax(game: &Game, player_mark: PlayMarkers, depth: i32, is_maximizing: boo
     let moves = game.get_available_plays();
     loop {
         // game ended so score the game.
-        if !score.is_none() { break; }
+        if score.is_some() { break; }
 
         let mut games = Vec::new();
         for m in &moves {
             let mut game_copy = game.copy();
-            game_copy.play(*m);
+            game_copy.play(*m).unwrap();
             games.push(game_copy);
         }
 
@@ -58,16 +82,10 @@ fn minimax(game: &Game, player_mark: PlayMarkers, depth: i32, is_maximizing: boo
         }
         if is_maximizing {
             let max_value = scores.iter().max();
-            score = match max_value {
-                Some(max) => Some(max + depth),
-                None => None,
-            }
+            score = max_value.map(|max| max + depth);
         } else {
             let min_value = scores.iter().min();
-            score = match min_value {
-                Some(min) => Some(min - depth),
-                None => None,
-            }
+            score = min_value.map(|min| min - depth);
         }
     }
     score.unwrap()
@@ -80,7 +98,7 @@ fn score_game(game: &Game, marker: PlayMarkers) -> Option<i32> {
             if marker == winner_marker { Some(10) } else { Some(-10) }
         }
         None => {
-            if game.is_over { Some(0) } else { None }
+            if game.is_over() { Some(0) } else { None }
         }
     }
 }
@@ -88,30 +106,42 @@ fn score_game(game: &Game, marker: PlayMarkers) -> Option<i32> {
 #[cfg(test)]
 mod ai_game {
     use crate::Game;
-    use crate::ai::get_best_move;
+    use crate::ai::{get_best_move, supports_size};
 
     #[test]
     fn gets_obvious_move() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for play in [0, 3, 1, 4] {
-            game.play(play);
+            game.play(play).unwrap();
         }
         assert_eq!(get_best_move(&game), 2)
     }
 
     #[test]
     fn avoids_trap() {
-        let mut game = Game::new();
-        game.play(0);
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
         assert_eq!(get_best_move(&game), 4)
     }
 
     #[test]
     fn avoids_l_trap() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for play in [0, 4, 8] {
-            game.play(play);
+            game.play(play).unwrap();
         }
-        assert!(vec![1u8, 3u8, 5u8, 7u8].contains(&get_best_move(&game)))
+        assert!([1u8, 3u8, 5u8, 7u8].contains(&get_best_move(&game)))
+    }
+
+    #[test]
+    fn only_searches_classic_board() {
+        assert!(supports_size(3));
+        assert!(!supports_size(4));
+    }
+
+    #[test]
+    #[should_panic(expected = "boards up to 3 x 3")]
+    fn refuses_larger_board() {
+        get_best_move(&Game::new(4, 4));
     }
 }
@@ -1,173 +1,519 @@
 use std::collections::HashMap;
+use std::time::Instant;
+use rand::Rng;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use crate::{Game, PlayMarkers};
+use crate::player::Player;
+use crate::scored_move::{MoveResult, ScoredMove};
+use crate::zobrist;
 
-pub fn get_best_move(game: &Game) -> u8
-{
-    let mut moves = game.get_available_plays();
-    let mut best_move: u8 = *moves.choose(&mut rand::thread_rng()).unwrap();
-    let active_marker = game.get_active_marker();
-    let mut move_scores: HashMap<u8, i32> = HashMap::new();
-    if should_min_max(moves.len()) {
-        loop {
-            if moves.is_empty() {
-                best_move = *get_max_key(&move_scores).unwrap();
-                break;
-            } else {
-                let play = moves.pop().unwrap();
-                let mut game_copy: Game = game.copy();
-                game_copy.play(play);
-                let score = mini_max(&game_copy, active_marker, 0);
-                move_scores.insert(play, score);
-            }
+pub const WIN_SCORE: i32 = 1_000_000;
+// Scores past this mean a forced win or loss; they are stored relative to the position in the table.
+const WIN_THRESHOLD: i32 = WIN_SCORE - 1_000;
+// Boards with more spaces than this are searched to a fixed depth instead of to the end.
+const FULL_SEARCH_SPACES: u8 = 16;
+const LIMITED_SEARCH_DEPTH: u8 = 4;
+// On large boards only spaces this close to a played space are considered.
+const CANDIDATE_DISTANCE: u8 = 1;
+const LARGE_BOARD_SPACES: u8 = 36;
+
+pub struct Ai2Player {
+    max_depth: u8,
+    blunder_chance: f64,
+    rng: StdRng,
+}
+
+impl Ai2Player {
+    pub fn new(rng: StdRng) -> Self {
+        Self {
+            max_depth: u8::MAX,
+            blunder_chance: 0.0,
+            rng,
+        }
+    }
+
+    // Looks at most max_depth moves ahead, and plays a random move instead with the given chance.
+    pub fn limited(max_depth: u8, blunder_chance: f64, rng: StdRng) -> Self {
+        Self {
+            max_depth,
+            blunder_chance,
+            rng,
         }
     }
-    best_move
 }
 
-fn mini_max(game: &Game, marker: PlayMarkers, depth: i32) -> i32 {
-    let mut score = get_game_score(marker, game);
-    let is_maximizing = game.get_active_marker() == marker;
-    loop {
-        if !score.is_none() { break; }
-        let games = get_possible_games(game);
-        let scores = score_games(marker, games, depth + 1);
-        let scores_iter = scores.iter();
-        if  is_maximizing {
-            score = match scores_iter.max() {
-                Some(value) => Some(value - depth),
-                None => None
-            };
+impl Player for Ai2Player {
+    fn choose_move(&mut self, game: &Game) -> u8 {
+        if self.rng.gen_bool(self.blunder_chance) {
+            *game.get_available_plays().choose(&mut self.rng).unwrap()
         } else {
-            score = match scores_iter.min() {
-                Some(value) => Some(*value),
-                None => None
-            };
+            get_best_move_to_depth(game, self.max_depth, &mut self.rng)
         }
     }
-    score.unwrap()
 }
 
-fn score_games(marker: PlayMarkers, games: Vec<Game>, depth: i32) -> Vec<i32> {
-    let mut scores = Vec::new();
-    for g in games {
-        scores.push(mini_max(&g, marker, depth));
+pub fn get_best_move(game: &Game) -> u8
+{
+    get_best_move_to_depth(game, u8::MAX, &mut rand::thread_rng())
+}
+
+pub fn get_best_move_to_depth<R: Rng>(game: &Game, max_depth: u8, rng: &mut R) -> u8 {
+    let moves = game.get_available_plays();
+    if should_min_max(moves.len(), game.get_board().get_space_count() as usize) {
+        let depth = get_search_depth(game).min(max_depth).max(1);
+        Search::new(game).get_best_move(depth).expect("A search without a deadline always finishes.")
+    } else {
+        *moves.choose(rng).unwrap()
     }
-    scores
 }
 
-fn get_possible_games(game: &Game) -> Vec<Game> {
+// Searches one move deeper at a time until the deadline, returning the best move of the deepest search
+// that finished. A search still running at the deadline is abandoned part way through.
+pub fn get_best_move_before<R: Rng>(game: &Game, deadline: Instant, rng: &mut R) -> u8 {
     let moves = game.get_available_plays();
-    let mut games = Vec::new();
-    for m in &moves {
-        let mut game_copy = game.copy();
-        game_copy.play(*m);
-        games.push(game_copy);
+    if !should_min_max(moves.len(), game.get_board().get_space_count() as usize) {
+        return *moves.choose(rng).unwrap();
     }
-    games
+    let mut search = Search::new(game);
+    search.deadline = Some(deadline);
+    let mut best_move = search.get_ordered_moves(None)[0];
+    for depth in 1..=moves.len() as u8 {
+        match search.get_best_move(depth) {
+            Some(space) => best_move = space,
+            None => break,
+        }
+    }
+    best_move
+}
+
+// Every available move scored for the side to move, best first. Ties keep the lowest space first.
+pub fn get_scored_moves(game: &Game) -> Vec<ScoredMove> {
+    if game.is_over() {
+        return Vec::new();
+    }
+    let depth = get_search_depth(game);
+    let reaches_end = depth as usize >= game.get_available_plays().len();
+    let mut moves = Search::new(game).get_scored_moves(depth, reaches_end);
+    moves.sort_by_key(|scored_move| std::cmp::Reverse(scored_move.score));
+    moves
+}
+
+// Score of the position for the given marker, searching to the end of the game: WIN_SCORE once the marker
+// has won, WIN_SCORE - n for a forced win n moves from now, the negatives of those for losses and 0 for a
+// draw. This replaced the old mini_max scale of about plus or minus 10, so those values no longer apply.
+pub fn score_position(game: &Game, marker: PlayMarkers) -> i32 {
+    let mut search = Search::new(game);
+    let depth = game.get_available_plays().len() as u8;
+    let score = search.nega_max(depth, 0, -WIN_SCORE, WIN_SCORE);
+    if game.get_active_marker() == marker { score } else { -score }
 }
 
-fn get_game_score(marker: PlayMarkers, game: &Game) -> Option<i32> {
-    let score = match game.get_winner() {
-        Some(winner) => {
-            if winner == marker { Some(10) } else { Some(-10) }
+fn get_search_depth(game: &Game) -> u8 {
+    let remaining = game.get_available_plays().len() as u8;
+    if game.get_board().get_space_count() <= FULL_SEARCH_SPACES {
+        remaining
+    } else {
+        remaining.min(LIMITED_SEARCH_DEPTH)
+    }
+}
+
+fn should_min_max(available_move_count: usize, space_count: usize) -> bool {
+    available_move_count < space_count && available_move_count > 1
+}
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct TableEntry {
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best_move: u8,
+}
+
+// Alpha-beta negamax over a single game, playing and taking back moves as it goes.
+struct Search {
+    game: Game,
+    hash: u64,
+    table: HashMap<u64, TableEntry>,
+    deadline: Option<Instant>,
+    is_stopped: bool,
+}
+
+impl Search {
+    fn new(game: &Game) -> Self {
+        Self {
+            game: game.copy(),
+            hash: zobrist::hash_board(game.get_board()),
+            table: HashMap::new(),
+            deadline: None,
+            is_stopped: false,
+        }
+    }
+
+    // None when the deadline passed before every move was searched.
+    fn get_best_move(&mut self, depth: u8) -> Option<u8> {
+        let moves = self.get_ordered_moves(None);
+        let mut best_move = moves[0];
+        let mut alpha = -WIN_SCORE;
+        for space in moves {
+            self.make_move(space);
+            let score = -self.nega_max(depth - 1, 1, -WIN_SCORE, -alpha);
+            self.unmake_move();
+            if self.is_stopped {
+                return None;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = space;
+            }
+        }
+        Some(best_move)
+    }
+
+    // Searches every move with a full window so each score is exact rather than a bound.
+    fn get_scored_moves(&mut self, depth: u8, reaches_end: bool) -> Vec<ScoredMove> {
+        let mut scored_moves = Vec::new();
+        for space in self.game.get_available_plays() {
+            self.make_move(space);
+            let score = -self.nega_max(depth - 1, 1, -WIN_SCORE, WIN_SCORE);
+            self.unmake_move();
+            scored_moves.push(ScoredMove { space, score, result: get_move_result(score, reaches_end) });
+        }
+        scored_moves
+    }
+
+    // Score for the side to move, from -WIN_SCORE (lost now) to WIN_SCORE.
+    fn nega_max(&mut self, depth: u8, ply: u8, mut alpha: i32, mut beta: i32) -> i32 {
+        if self.game.get_winner().is_some() {
+            // Only the player who just moved can have won.
+            return -(WIN_SCORE - ply as i32);
+        }
+        if self.game.is_over() {
+            return 0;
+        }
+        if depth == 0 {
+            return self.evaluate();
         }
-        None => {
-            if game.is_over { Some(0) } else { None }
+        if self.is_out_of_time() {
+            return 0;
         }
-    };
-    score
+        let original_alpha = alpha;
+        let mut table_move = None;
+        if let Some(entry) = self.table.get(&self.hash) {
+            table_move = Some(entry.best_move);
+            if entry.depth >= depth {
+                let score = from_table_score(entry.score, ply);
+                match entry.bound {
+                    Bound::Exact => return score,
+                    Bound::Lower => alpha = alpha.max(score),
+                    Bound::Upper => beta = beta.min(score),
+                }
+                if alpha >= beta {
+                    return score;
+                }
+            }
+        }
+        let mut best_score = -WIN_SCORE;
+        let mut best_move = 0;
+        for space in self.get_ordered_moves(table_move) {
+            self.make_move(space);
+            let score = -self.nega_max(depth - 1, ply + 1, -beta, -alpha);
+            self.unmake_move();
+            if score > best_score {
+                best_score = score;
+                best_move = space;
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta || self.is_stopped {
+                break;
+            }
+        }
+        // Scores from an abandoned search are incomplete, so they are kept out of the table.
+        if self.is_stopped {
+            return 0;
+        }
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(self.hash, TableEntry {
+            depth,
+            score: to_table_score(best_score, ply),
+            bound,
+            best_move,
+        });
+        best_score
+    }
+
+    fn is_out_of_time(&mut self) -> bool {
+        if !self.is_stopped && self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.is_stopped = true;
+        }
+        self.is_stopped
+    }
+
+    fn make_move(&mut self, space: u8) {
+        let marker = self.game.get_active_marker();
+        self.game.play(space).unwrap();
+        self.hash ^= zobrist::get_key(space, &marker);
+    }
+
+    fn unmake_move(&mut self) {
+        let space = self.game.undo().unwrap();
+        let marker = self.game.get_active_marker();
+        self.hash ^= zobrist::get_key(space, &marker);
+    }
+
+    // Tries the table's best move first, then the spaces that belong to the most winning lines.
+    fn get_ordered_moves(&self, table_move: Option<u8>) -> Vec<u8> {
+        let mut moves = self.get_candidate_moves();
+        moves.sort_by_key(|space| {
+            let is_table_move = Some(*space) == table_move;
+            (!is_table_move, std::cmp::Reverse(self.game.get_winning_lines_through(space).len()))
+        });
+        moves
+    }
+
+    fn get_candidate_moves(&self) -> Vec<u8> {
+        let moves = self.game.get_available_plays();
+        let board = &self.game.get_board();
+        if board.get_space_count() <= LARGE_BOARD_SPACES || board.get_played_count() == 0 {
+            return moves;
+        }
+        moves.into_iter().filter(|space| self.is_near_played_space(*space)).collect()
+    }
+
+    fn is_near_played_space(&self, space: u8) -> bool {
+        let size = self.game.get_board().get_size();
+        let (row, column) = (space / size, space % size);
+        let (first_row, last_row) = (row.saturating_sub(CANDIDATE_DISTANCE), (row + CANDIDATE_DISTANCE).min(size - 1));
+        let (first_column, last_column) = (column.saturating_sub(CANDIDATE_DISTANCE), (column + CANDIDATE_DISTANCE).min(size - 1));
+        (first_row..=last_row).any(|r| {
+            (first_column..=last_column).any(|c| self.game.get_board().is_space_played(&(r * size + c)))
+        })
+    }
+
+    // Rewards lines that only the side to move can still complete, and penalises the opponent's.
+    fn evaluate(&self) -> i32 {
+        let marker = self.game.get_active_marker();
+        let own_spaces = *self.game.get_board().get_marker_spaces(&marker);
+        let opponent_spaces = *self.game.get_board().get_marker_spaces(&marker.get_opponent());
+        let mut score = 0;
+        for line in self.game.get_winning_lines() {
+            let own = (own_spaces & *line).count() as i32;
+            let opponent = (opponent_spaces & *line).count() as i32;
+            if opponent == 0 {
+                score += get_line_value(own);
+            } else if own == 0 {
+                score -= get_line_value(opponent);
+            }
+        }
+        score
+    }
 }
 
-fn should_min_max(available_move_count: usize) -> bool {
-    available_move_count < 9 && available_move_count > 1
+// Grows fourfold per marker, capped so a full board stays well below WIN_THRESHOLD.
+fn get_line_value(marker_count: i32) -> i32 {
+    (1 << (2 * marker_count.min(4))) - 1
 }
 
-pub fn get_max_key<K, V>(a_hash_map: &HashMap<K, V>) -> Option<&K> where V: Ord,
-{
-    a_hash_map
-        .iter()
-        .max_by(|a, b| a.1.cmp(&b.1))
-        .map(|(k, _v)| k)
+fn get_move_result(score: i32, reaches_end: bool) -> MoveResult {
+    if score > WIN_THRESHOLD {
+        MoveResult::Win((WIN_SCORE - score) as u8)
+    } else if score < -WIN_THRESHOLD {
+        MoveResult::Loss((WIN_SCORE + score) as u8)
+    } else if reaches_end {
+        MoveResult::Draw
+    } else {
+        MoveResult::Unclear
+    }
 }
 
+fn to_table_score(score: i32, ply: u8) -> i32 {
+    if score > WIN_THRESHOLD {
+        score + ply as i32
+    } else if score < -WIN_THRESHOLD {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn from_table_score(score: i32, ply: u8) -> i32 {
+    if score > WIN_THRESHOLD {
+        score - ply as i32
+    } else if score < -WIN_THRESHOLD {
+        score + ply as i32
+    } else {
+        score
+    }
+}
 
 #[cfg(test)]
 mod ai_game_2 {
-    use crate::ai2::{mini_max, get_best_move};
+    use rand::SeedableRng;
+    use std::time::{Duration, Instant};
+    use rand::rngs::StdRng;
+    use crate::ai2::{get_best_move, get_best_move_before, get_best_move_to_depth, get_scored_moves, score_position, WIN_SCORE};
+    use crate::scored_move::MoveResult;
     use crate::{Game, PlayMarkers};
 
     #[test]
     fn mini_max_scores_winning_game() {
-        let mut game = Game::new();
-        for play in [0, 4, 6, 2, 3] {
-            game.play(play);
-        }
-        assert_eq!(10, mini_max(&game, PlayMarkers::X, 0))
+        let game: Game = "X_O/XO_/X__ o".parse().unwrap();
+        assert_eq!(WIN_SCORE, score_position(&game, PlayMarkers::X))
     }
 
     #[test]
     fn mini_max_scores_loosing_game() {
-        let mut game = Game::new();
-        for play in [0, 4, 6, 2, 3] {
-            game.play(play);
-        }
-        assert_eq!(-10, mini_max(&game, PlayMarkers::O, 0))
+        let game: Game = "X_O/XO_/X__ o".parse().unwrap();
+        assert_eq!(-WIN_SCORE, score_position(&game, PlayMarkers::O))
     }
 
     #[test]
     fn mini_max_scores_next_play_winning_game() {
-        let mut game = Game::new();
-        for play in [0, 4, 2, 5] {
-            game.play(play);
-        }
-        assert_eq!(10, mini_max(&game, PlayMarkers::X, 0))
+        let game: Game = "X_X/_OO/___ x".parse().unwrap();
+        assert_eq!(WIN_SCORE - 1, score_position(&game, PlayMarkers::X))
     }
 
     #[test]
     fn mini_max_scores_next_play_loosing_game() {
-        let mut game = Game::new();
-        for play in [0, 4, 2, 3] {
-            game.play(play);
-        }
-        assert_eq!(-11, mini_max(&game, PlayMarkers::O, 0))
+        let game: Game = "X_X/OO_/___ x".parse().unwrap();
+        assert_eq!(-(WIN_SCORE - 1), score_position(&game, PlayMarkers::O))
+    }
+
+    #[test]
+    fn mini_max_scores_drawn_game() {
+        let game: Game = "XOX/XOO/OXX o".parse().unwrap();
+        assert_eq!(0, score_position(&game, PlayMarkers::X));
+        assert_eq!(0, score_position(&game, PlayMarkers::O))
     }
 
     #[test]
     fn get_best_gets_offensive_move() {
-        let mut game = Game::new();
-        for play in [0, 4, 2, 3] {
-            game.play(play);
-        }
+        let game: Game = "X_X/OO_/___ x".parse().unwrap();
         assert_eq!(1, get_best_move(&game))
     }
 
     #[test]
     fn get_best_gets_defensive_move() {
-        let mut game = Game::new();
-        for play in [0] {
-            game.play(play);
-        }
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
         assert_eq!(4, get_best_move(&game))
     }
 
     #[test]
     fn get_best_prevents_winner() {
-        let mut game = Game::new();
-        for play in [0, 8, 1] {
-            game.play(play);
-        }
+        let game: Game = "XX_/___/__O o".parse().unwrap();
         assert_eq!(2, get_best_move(&game))
     }
 
     #[test]
     fn get_best_move_traps_player() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for play in [0, 1] {
-            game.play(play);
+            game.play(play).unwrap();
         }
         println!("{}", get_best_move(&game));
-        assert!(vec![3u8, 4u8, 6u8].contains(&get_best_move(&game)))
+        assert!([3u8, 4u8, 6u8].contains(&get_best_move(&game)))
+    }
+
+    #[test]
+    fn shallow_search_takes_win() {
+        let game: Game = "X_X/OO_/___ x".parse().unwrap();
+        assert_eq!(1, get_best_move_to_depth(&game, 1, &mut StdRng::seed_from_u64(0)))
+    }
+
+    #[test]
+    fn shallow_search_blocks_win() {
+        let game: Game = "XX_/___/__O o".parse().unwrap();
+        assert_eq!(2, get_best_move_to_depth(&game, 2, &mut StdRng::seed_from_u64(0)))
+    }
+
+    #[test]
+    fn search_with_time_blocks_win() {
+        let game: Game = "XX_/___/__O o".parse().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        assert_eq!(2, get_best_move_before(&game, deadline, &mut StdRng::seed_from_u64(0)))
+    }
+
+    #[test]
+    fn search_past_deadline_still_moves() {
+        let mut game = Game::new(15, 5);
+        for play in [112, 96, 113] {
+            game.play(play).unwrap();
+        }
+        let space = get_best_move_before(&game, Instant::now(), &mut StdRng::seed_from_u64(0));
+        assert!(game.is_valid_move(&space))
+    }
+
+    #[test]
+    fn seeded_opening_move_is_reproducible() {
+        let game = Game::new(3, 3);
+        for seed in 0..10 {
+            let first = get_best_move_to_depth(&game, u8::MAX, &mut StdRng::seed_from_u64(seed));
+            let second = get_best_move_to_depth(&game, u8::MAX, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(first, second);
+        }
     }
 
+    #[test]
+    fn searches_larger_board() {
+        let mut game = Game::new(4, 4);
+        for play in [0, 5, 1, 6, 2] {
+            game.play(play).unwrap();
+        }
+        assert_eq!(3, get_best_move(&game))
+    }
+
+    #[test]
+    fn blocks_open_three_on_gomoku_board() {
+        let mut game = Game::new(15, 5);
+        for play in [112, 0, 113, 14, 114] {
+            game.play(play).unwrap();
+        }
+        assert!([111u8, 115u8].contains(&get_best_move(&game)))
+    }
+
+    #[test]
+    fn scores_every_move_with_distance_to_result() {
+        let game: Game = "X_X/OO_/___ x".parse().unwrap();
+        let results: Vec<(u8, MoveResult)> = get_scored_moves(&game).iter().map(|scored| (scored.space, scored.result)).collect();
+        assert_eq!(results, vec![
+            (1, MoveResult::Win(1)),
+            (5, MoveResult::Win(3)),
+            (6, MoveResult::Loss(2)),
+            (7, MoveResult::Loss(2)),
+            (8, MoveResult::Loss(2)),
+        ]);
+    }
+
+    #[test]
+    fn scores_opening_replies() {
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
+        let scored_moves = get_scored_moves(&game);
+        assert_eq!(scored_moves.len(), 8);
+        assert_eq!((scored_moves[0].space, scored_moves[0].result), (4, MoveResult::Draw));
+        assert!(scored_moves[1..].iter().all(|scored| matches!(scored.result, MoveResult::Loss(_))));
+    }
+
+    #[test]
+    fn scores_are_unclear_before_search_reaches_end() {
+        let mut game = Game::new(5, 4);
+        game.play(12).unwrap();
+        let scored_moves = get_scored_moves(&game);
+        assert_eq!(scored_moves.len(), 24);
+        assert!(scored_moves.iter().all(|scored| scored.result == MoveResult::Unclear));
+    }
 }
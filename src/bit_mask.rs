@@ -0,0 +1,89 @@
+use std::ops::{BitAnd, BitOr};
+
+// Four words cover every space of the largest supported board (15 x 15 = 225).
+const WORDS: usize = 4;
+
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct BitMask([u64; WORDS]);
+
+impl BitMask {
+    pub const EMPTY: BitMask = BitMask([0; WORDS]);
+
+    pub fn from_spaces(spaces: &[u8]) -> Self {
+        let mut mask = Self::EMPTY;
+        for space in spaces {
+            mask.set(*space);
+        }
+        mask
+    }
+
+    pub fn set(&mut self, space: u8) {
+        self.0[space as usize / 64] |= 1 << (space % 64);
+    }
+
+    pub fn clear(&mut self, space: u8) {
+        self.0[space as usize / 64] &= !(1 << (space % 64));
+    }
+
+    pub fn contains(&self, space: u8) -> bool {
+        self.0[space as usize / 64] & (1 << (space % 64)) != 0
+    }
+
+    pub fn contains_all(&self, other: &BitMask) -> bool {
+        *self & *other == *other
+    }
+
+    pub fn count(&self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+}
+
+impl BitOr for BitMask {
+    type Output = BitMask;
+
+    fn bitor(self, rhs: BitMask) -> BitMask {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(rhs.0) {
+            *word |= other;
+        }
+        BitMask(words)
+    }
+}
+
+impl BitAnd for BitMask {
+    type Output = BitMask;
+
+    fn bitand(self, rhs: BitMask) -> BitMask {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(rhs.0) {
+            *word &= other;
+        }
+        BitMask(words)
+    }
+}
+
+#[cfg(test)]
+mod masks {
+    use crate::bit_mask::BitMask;
+
+    #[test]
+    fn sets_and_clears_spaces_across_words() {
+        let mut mask = BitMask::EMPTY;
+        for space in [0, 63, 64, 224] {
+            mask.set(space);
+        }
+        assert_eq!(mask.count(), 4);
+        assert!(mask.contains(64));
+        assert!(!mask.contains(65));
+        mask.clear(64);
+        assert!(!mask.contains(64));
+        assert!(mask.contains(224));
+    }
+
+    #[test]
+    fn contains_all_of_a_line() {
+        let line = BitMask::from_spaces(&[2, 4, 6]);
+        assert!(BitMask::from_spaces(&[0, 2, 4, 6]).contains_all(&line));
+        assert!(!BitMask::from_spaces(&[0, 2, 4]).contains_all(&line));
+    }
+}
@@ -1,37 +1,248 @@
-use std::collections::HashMap;
+use crate::bit_mask::BitMask;
 use crate::play_markers::PlayMarkers;
+use crate::symmetry::{SymmetricBoard, Symmetry};
 
+pub const MAX_SIZE: u8 = 15;
+
+// Serialized as {"size": 3, "spaces": "X___O____"}, one character per space from left to right and top to bottom.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "BoardData", try_from = "BoardData"))]
 pub struct Board {
-    pub(crate) spaces: HashMap<u8, PlayMarkers>,
+    size: u8,
+    x_spaces: BitMask,
+    o_spaces: BitMask,
 }
 
 impl Board {
-    pub fn new() -> Self {
+    pub fn new(size: u8) -> Self {
+        assert!((1..=MAX_SIZE).contains(&size), "Board size must be between 1 and {}.", MAX_SIZE);
         Self {
-            spaces: HashMap::new()
+            size,
+            x_spaces: BitMask::EMPTY,
+            o_spaces: BitMask::EMPTY,
         }
     }
 
     pub fn copy(&self) -> Self {
-        Self {
-            spaces: self.spaces.clone(),
-        }
+        *self
     }
 
     pub fn play(&mut self, space: u8, marker: &PlayMarkers) {
-        self.spaces.insert(space, *marker);
+        match marker {
+            PlayMarkers::X => self.x_spaces.set(space),
+            PlayMarkers::O => self.o_spaces.set(space),
+        }
+    }
+
+    pub fn clear(&mut self, space: u8) {
+        self.x_spaces.clear(space);
+        self.o_spaces.clear(space);
+    }
+
+    pub fn get_size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn get_space_count(&self) -> u8 {
+        self.size * self.size
+    }
+
+    pub fn get_played_count(&self) -> u8 {
+        (self.x_spaces | self.o_spaces).count() as u8
+    }
+
+    pub fn get_marker_spaces(&self, marker: &PlayMarkers) -> &BitMask {
+        match marker {
+            PlayMarkers::X => &self.x_spaces,
+            PlayMarkers::O => &self.o_spaces,
+        }
     }
 
     pub fn is_full(&self) -> bool {
-        self.spaces.len() == 9
+        self.get_played_count() == self.get_space_count()
     }
 
     pub fn is_space_played(&self, space: &u8) -> bool {
-        self.spaces.contains_key(&space)
+        self.x_spaces.contains(*space) || self.o_spaces.contains(*space)
     }
 
     pub fn get_space_marker(&self, space: &u8) -> Option<&PlayMarkers> {
-        self.spaces.get(space)
+        if self.x_spaces.contains(*space) {
+            Some(&PlayMarkers::X)
+        } else if self.o_spaces.contains(*space) {
+            Some(&PlayMarkers::O)
+        } else {
+            None
+        }
     }
 
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let mut board = Board::new(self.size);
+        for space in 0..self.get_space_count() {
+            if let Some(marker) = self.get_space_marker(&space) {
+                board.play(symmetry.map_space(space, self.size), marker);
+            }
+        }
+        board
+    }
+
+    // The board under each of the eight rotations and reflections, starting with the identity.
+    pub fn symmetries(&self) -> Vec<SymmetricBoard> {
+        Symmetry::ALL.iter().map(|symmetry| SymmetricBoard {
+            symmetry: *symmetry,
+            board: self.transform(*symmetry),
+            mapping: symmetry.get_mapping(self.size),
+        }).collect()
+    }
+
+    // The same board for every orientation of a position, so equivalent positions can share cache entries,
+    // opening book moves and statistics. Ties go to the first symmetry, so the identity is kept when possible.
+    pub fn canonical(&self) -> SymmetricBoard {
+        self.symmetries().into_iter()
+            .min_by_key(|symmetric| (symmetric.board.x_spaces, symmetric.board.o_spaces))
+            .expect("Every board has the identity symmetry.")
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BoardData {
+    size: u8,
+    spaces: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<Board> for BoardData {
+    fn from(board: Board) -> Self {
+        let spaces = (0..board.get_space_count()).map(|space| match board.get_space_marker(&space) {
+            Some(PlayMarkers::X) => 'X',
+            Some(PlayMarkers::O) => 'O',
+            None => '_',
+        });
+        Self {
+            size: board.size,
+            spaces: spaces.collect(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<BoardData> for Board {
+    type Error = String;
+
+    fn try_from(data: BoardData) -> Result<Self, Self::Error> {
+        if !(1..=MAX_SIZE).contains(&data.size) {
+            return Err(format!("Board size must be between 1 and {}.", MAX_SIZE));
+        }
+        let mut board = Board::new(data.size);
+        if data.spaces.chars().count() != board.get_space_count() as usize {
+            return Err(format!("Board of size {} needs {} spaces.", data.size, board.get_space_count()));
+        }
+        for (space, marker) in data.spaces.chars().enumerate() {
+            match marker {
+                'X' => board.play(space as u8, &PlayMarkers::X),
+                'O' => board.play(space as u8, &PlayMarkers::O),
+                '_' => {}
+                _ => return Err(format!("Invalid space '{}'.", marker)),
+            }
+        }
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod board_orientations {
+    use crate::board::Board;
+    use crate::play_markers::PlayMarkers;
+    use crate::symmetry::Symmetry;
+
+    fn board_with(size: u8, x_spaces: &[u8], o_spaces: &[u8]) -> Board {
+        let mut board = Board::new(size);
+        for space in x_spaces {
+            board.play(*space, &PlayMarkers::X);
+        }
+        for space in o_spaces {
+            board.play(*space, &PlayMarkers::O);
+        }
+        board
+    }
+
+    #[test]
+    fn copy_is_independent() {
+        let board = board_with(3, &[0], &[]);
+        let mut copy = board.copy();
+        copy.play(4, &PlayMarkers::O);
+        assert_eq!(board.get_played_count(), 1);
+        assert_eq!(copy.get_played_count(), 2);
+    }
+
+    #[test]
+    fn lists_eight_orientations() {
+        let board = board_with(3, &[0], &[1]);
+        let symmetries = board.symmetries();
+        assert_eq!(symmetries.len(), 8);
+        assert_eq!(symmetries[0].board, board);
+        assert_eq!(symmetries[1].symmetry, Symmetry::RotateQuarter);
+        assert_eq!(symmetries[1].board, board_with(3, &[2], &[5]));
+        assert_eq!(symmetries[1].mapping, vec![2, 5, 8, 1, 4, 7, 0, 3, 6]);
+    }
+
+    #[test]
+    fn equivalent_positions_share_canonical_board() {
+        let corners: Vec<Board> = [0, 2, 6, 8].iter().map(|space| board_with(3, &[*space], &[4]).canonical().board).collect();
+        assert!(corners.iter().all(|board| *board == corners[0]));
+        let edges: Vec<Board> = [1, 3, 5, 7].iter().map(|space| board_with(3, &[*space], &[]).canonical().board).collect();
+        assert!(edges.iter().all(|board| *board == edges[0]));
+        assert_ne!(corners[0], edges[0]);
+        let larger = board_with(4, &[5, 0], &[15]);
+        assert_eq!(larger.transform(Symmetry::FlipDiagonal).canonical().board, larger.canonical().board);
+    }
+
+    #[test]
+    fn symmetric_position_keeps_identity() {
+        let canonical = board_with(3, &[4], &[]).canonical();
+        assert_eq!(canonical.symmetry, Symmetry::Identity);
+        assert_eq!(canonical.board, board_with(3, &[4], &[]));
+    }
+
+    #[test]
+    fn maps_moves_back_from_canonical_board() {
+        let board = board_with(3, &[8], &[4]);
+        let canonical = board.canonical();
+        assert_eq!(canonical.board, board.transform(canonical.symmetry));
+        assert_eq!(canonical.to_original(canonical.mapping[6]), 6);
+        for space in 0..9 {
+            assert_eq!(board.get_space_marker(&space), canonical.board.get_space_marker(&canonical.mapping[space as usize]));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod board_serde {
+    use crate::board::Board;
+    use crate::play_markers::PlayMarkers;
+
+    #[test]
+    fn serializes_spaces_as_text() {
+        let mut board = Board::new(3);
+        board.play(0, &PlayMarkers::X);
+        board.play(4, &PlayMarkers::O);
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, r#"{"size":3,"spaces":"X___O____"}"#);
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn rejects_invalid_boards() {
+        assert!(serde_json::from_str::<Board>(r#"{"size":16,"spaces":""}"#).is_err());
+        assert!(serde_json::from_str::<Board>(r#"{"size":2,"spaces":"X__"}"#).is_err());
+        assert!(serde_json::from_str::<Board>(r#"{"size":2,"spaces":"X__Z"}"#).is_err());
+    }
+
+    #[test]
+    fn serializes_markers_by_name() {
+        assert_eq!(serde_json::to_string(&PlayMarkers::O).unwrap(), r#""O""#);
+        assert_eq!(serde_json::from_str::<PlayMarkers>(r#""X""#).unwrap(), PlayMarkers::X);
+    }
 }
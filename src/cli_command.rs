@@ -0,0 +1,73 @@
+use std::str::FromStr;
+
+pub const HELP: &str = "Commands:
+  :undo         take back your last move
+  :hint         show where each move leads
+  :save <file>  save the game to a file
+  :resign       give up the game
+  :new          start a new game
+  :help         show this list
+  :quit         leave without finishing
+";
+
+// A line starting with ":" typed in place of a move.
+#[derive(Debug, PartialEq, Clone)]
+pub enum CliCommand {
+    Undo,
+    Hint,
+    Save(String),
+    Resign,
+    New,
+    Help,
+    Quit,
+}
+
+impl FromStr for CliCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match s.trim().split_once(' ') {
+            Some((name, argument)) => (name, Some(argument.trim())),
+            None => (s.trim(), None),
+        };
+        let command = match name {
+            ":undo" => CliCommand::Undo,
+            ":hint" => CliCommand::Hint,
+            ":save" => {
+                return match argument {
+                    Some(path) => Ok(CliCommand::Save(path.to_string())),
+                    None => Err("Usage: :save <file>".to_string()),
+                }
+            }
+            ":resign" => CliCommand::Resign,
+            ":new" => CliCommand::New,
+            ":help" => CliCommand::Help,
+            ":quit" => CliCommand::Quit,
+            _ => return Err(format!("Unknown command {}. Type :help for commands.", name)),
+        };
+        match argument {
+            Some(_) => Err(format!("Usage: {}", name)),
+            None => Ok(command),
+        }
+    }
+}
+
+#[cfg(test)]
+mod command_parser {
+    use crate::cli_command::CliCommand;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(":undo".parse(), Ok(CliCommand::Undo));
+        assert_eq!(":hint".parse(), Ok(CliCommand::Hint));
+        assert_eq!(" :quit ".parse(), Ok(CliCommand::Quit));
+        assert_eq!(":save games/one.txt".parse(), Ok(CliCommand::Save("games/one.txt".to_string())));
+    }
+
+    #[test]
+    fn reports_usage_errors() {
+        assert_eq!(":save".parse::<CliCommand>(), Err("Usage: :save <file>".to_string()));
+        assert_eq!(":undo 2".parse::<CliCommand>(), Err("Usage: :undo".to_string()));
+        assert_eq!(":play".parse::<CliCommand>(), Err("Unknown command :play. Type :help for commands.".to_string()));
+    }
+}
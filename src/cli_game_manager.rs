@@ -1,88 +1,195 @@
+use std::fs;
 use std::io::{BufRead, Write};
-use crate::ai2::get_best_move;
+use clap::ArgEnum;
+use rand::rngs::StdRng;
 use crate::Game;
+use crate::ai2;
+use crate::cli_command::{CliCommand, HELP};
+use crate::difficulty::Difficulty;
+use crate::game_record::GameRecord;
+use crate::move_outcome::{MoveError, MoveOutcome};
+use crate::notation::MoveNotation;
+use crate::player::Player;
 use crate::play_markers::PlayMarkers;
+use crate::scoreboard::Scoreboard;
+
+pub const MODE_TAG: &str = "Mode";
+pub const DIFFICULTY_TAG: &str = "Difficulty";
 
 #[derive(clap::ArgEnum, Clone, PartialEq)]
 pub enum GameMode {
     AiFirst,
     AiLast,
-    LOCAL,
+    Local,
+}
+
+impl GameMode {
+    pub fn is_ai(&self, marker: &PlayMarkers) -> bool {
+        matches!((self, marker), (GameMode::AiFirst, PlayMarkers::X) | (GameMode::AiLast, PlayMarkers::O))
+    }
+
+    // The same players with the other one moving first.
+    pub fn swapped(&self) -> GameMode {
+        match self {
+            GameMode::AiFirst => GameMode::AiLast,
+            GameMode::AiLast => GameMode::AiFirst,
+            GameMode::Local => GameMode::Local,
+        }
+    }
 }
 
 pub struct CLIGameManager {
     game: Game,
     error: Option<String>,
+    message: Option<String>,
     mode: GameMode,
+    difficulty: Difficulty,
+    notation: MoveNotation,
+    ai: Box<dyn Player>,
+    resigned: Option<PlayMarkers>,
+    quit: bool,
+    // Marker of the human, or of player 1 in local games, which changes between games of a match.
+    player_one: PlayMarkers,
 }
 
 impl CLIGameManager {
-    pub fn new(mode: GameMode) -> Self {
+    pub fn new(mode: GameMode, game: Game, difficulty: Difficulty, rng: StdRng) -> Self {
         Self {
-            game: Game::new(),
+            game,
             error: None,
+            message: None,
+            difficulty,
+            notation: MoveNotation::Index,
+            ai: difficulty.get_player(rng),
+            resigned: None,
+            quit: false,
+            player_one: if mode.is_ai(&PlayMarkers::X) { PlayMarkers::O } else { PlayMarkers::X },
             mode,
         }
     }
 
+    pub fn with_notation(mut self, notation: MoveNotation) -> Self {
+        self.notation = notation;
+        self
+    }
+
     pub fn start<R, W>(&mut self, mut reader: R, mut writer: W) where R: BufRead, W: Write {
-        if self.mode == GameMode::AiFirst {
+        if self.is_ai_turn() {
             self.ai_play();
         }
         self.print(&mut writer);
-        while !self.game.is_over {
+        while !self.is_finished() {
             self.input_play(&mut reader);
+            if self.quit {
+                break;
+            }
             self.print(&mut writer);
         }
     }
 
+    // Plays games until one side has won a majority of best_of, swapping who moves first each game.
+    pub fn start_match<R, W>(&mut self, best_of: u32, mut reader: R, mut writer: W) where R: BufRead, W: Write {
+        let mut scoreboard = match self.mode {
+            GameMode::Local => Scoreboard::new("Player 1", "Player 2"),
+            _ => Scoreboard::new("You", "AI"),
+        };
+        for game_number in 1..=best_of {
+            let _result = writeln!(writer, "Game {} of {}\n", game_number, best_of);
+            self.start(&mut reader, &mut writer);
+            if self.quit {
+                break;
+            }
+            scoreboard.record(self.player_one, self.get_match_winner());
+            let _result = writeln!(writer, "{}", scoreboard);
+            if scoreboard.is_decided(best_of) {
+                break;
+            }
+            self.swap_sides();
+        }
+        let _result = write!(writer, "{}", scoreboard.summary());
+    }
+
+    // Quits at the end of input, otherwise handles one line as a command or a move.
     pub fn input_play<R>(&mut self, mut reader: R) where R: BufRead {
         let mut s = String::new();
-        let mut space = None;
-        while space.is_none() {
-            reader.read_line(&mut s).expect("Unable to read.");
-            space = match s.trim().parse::<u8>() {
-                Ok(n) => Some(n),
-                Err(_) => {
-                    self.error = Some(format!("Error: {} is not valid.\n", s.trim()));
-                    break;
+        let read = reader.read_line(&mut s).expect("Unable to read.");
+        if read == 0 {
+            self.quit = true;
+            return;
+        }
+        let input = s.trim();
+        if input.starts_with(':') {
+            match input.parse() {
+                Ok(command) => self.run_command(command),
+                Err(usage) => {
+                    self.message = None;
+                    self.error = Some(format!("Error: {}\n", usage));
                 }
-            };
+            }
+            return;
         }
-        if !space.is_none() {
-            self.play(space.unwrap());
+        match self.notation.parse(input, self.game.get_board().get_size()) {
+            Some(space) => self.play(space),
+            None => self.error = Some(format!("Error: {} is not valid.\n", input)),
         }
     }
 
-    pub fn play(&mut self, space: u8) {
-        if !self.game.is_valid_move(&space) {
-            self.set_move_error(&space);
-            return;
+    pub fn run_command(&mut self, command: CliCommand) {
+        self.message = None;
+        self.error = None;
+        match command {
+            CliCommand::Undo => self.undo(),
+            CliCommand::Hint => self.hint(),
+            CliCommand::Save(path) => self.save(&path),
+            CliCommand::Resign => self.resigned = Some(self.game.get_active_marker()),
+            CliCommand::New => self.new_game(),
+            CliCommand::Help => self.message = Some(HELP.to_string()),
+            CliCommand::Quit => self.quit = true,
         }
-        match &self.mode {
-            GameMode::LOCAL => {
-                self.game.play(space);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.game.is_over() || self.resigned.is_some() || self.quit
+    }
+
+    pub fn play(&mut self, space: u8) {
+        self.message = None;
+        match self.game.play(space) {
+            Ok(outcome) => {
+                self.error = None;
+                if self.mode != GameMode::Local && outcome == MoveOutcome::Continue {
+                    self.ai_play();
+                }
+            }
+            Err(MoveError::Occupied(space)) => {
+                let position = self.notation.format(space, self.game.get_board().get_size());
+                self.error = Some(format!("Error: Can't play in position {}, as it has been already played.\n", position));
             }
-            GameMode::AiFirst | GameMode::AiLast => {
-                self.game.play(space);
-                if !self.game.is_over { self.ai_play(); }
+            Err(error) => {
+                self.error = Some(format!("Error: {}\n", error));
             }
         }
     }
 
     pub fn print<W>(&self, mut writer: W) where W: Write {
-        let mut output = self.format_board_display();
-        if !self.game.get_winner().is_none() {
+        let mut output = Self::format_board_display(&self.game);
+        if let Some(marker) = self.resigned {
+            let winner = Self::get_display_marker(&marker.get_opponent());
+            output += format!("{} resigns. {} Wins!\n", Self::get_display_marker(&marker), winner).as_str();
+        } else if self.game.get_winner().is_some() {
             output += self.winner_display().as_str();
-        } else if self.game.is_over {
+        } else if self.game.is_over() {
             output += self.game_over_display();
         } else {
             output += self.active_turn_display().as_str();
             output += self.available_plays_display().as_str();
-            if !self.error.is_none() {
-                output += self.error.as_ref().unwrap().as_str();
+            if let Some(error) = &self.error {
+                output += error.as_str();
+            }
+            if let Some(message) = &self.message {
+                output += message.as_str();
             }
-            output += "Enter number: \n";
+            output += self.notation.get_prompt();
         }
         // println!("{}", output);
         let _result = writeln!(writer, "{}", output);
@@ -92,8 +199,9 @@ impl CLIGameManager {
         let mut display = String::new();
         let available_plays = self.game.get_available_plays();
         let mut available_plays_formatted = Vec::new();
+        let size = self.game.get_board().get_size();
         for play in available_plays {
-            available_plays_formatted.push(play.to_string())
+            available_plays_formatted.push(self.notation.format(play, size))
         }
         display += "Available spaces in order from left to right and top to bottom: ";
         display += &*available_plays_formatted.join(", ");
@@ -104,7 +212,7 @@ impl CLIGameManager {
     fn active_turn_display(&self) -> String {
         let mut turn_display = String::new();
         let marker = self.game.get_active_marker();
-        let marker_display = self.get_display_marker(&marker);
+        let marker_display = Self::get_display_marker(&marker);
         turn_display += marker_display;
         turn_display += "'s turn!\n";
         turn_display
@@ -113,8 +221,8 @@ impl CLIGameManager {
     fn winner_display(&self) -> String {
         let mut display = String::new();
         let winner = self.game.get_winner();
-        if !winner.is_none() {
-            display += format!("{} Wins!\n", self.get_display_marker(&winner.unwrap())).as_str();
+        if let Some(winner) = winner {
+            display += format!("{} Wins!\n", Self::get_display_marker(&winner)).as_str();
         }
         display
     }
@@ -123,13 +231,20 @@ impl CLIGameManager {
         "Game Over!\n"
     }
 
-    fn format_board_display(&self) -> String {
+    // Once a game is won, markers outside the completed lines are lowercased so the lines stand out.
+    pub fn format_board_display(game: &Game) -> String {
         let mut board_display = String::new();
-        for space in 1..10 {
-            let marker = &self.game.board.get_space_marker(&(space - 1));
-            let space_display = if marker.is_none() { "_" } else { self.get_display_marker(marker.unwrap()) };
-            board_display += space_display;
-            if space % 3 != 0 {
+        let size = game.get_board().get_size();
+        let is_won = game.get_winner().is_some();
+        for space in 1..=game.get_board().get_space_count() {
+            let marker = game.get_board().get_space_marker(&(space - 1));
+            let space_display = match marker {
+                Some(marker) if is_won && !game.is_in_completed_line(space - 1) => Self::get_display_marker(marker).to_lowercase(),
+                Some(marker) => Self::get_display_marker(marker).to_string(),
+                None => "_".to_string(),
+            };
+            board_display += &space_display;
+            if space % size != 0 {
                 board_display += "|";
             } else {
                 board_display += "\n";
@@ -138,36 +253,102 @@ impl CLIGameManager {
         board_display
     }
 
-    fn set_move_error(&mut self, space: &u8) {
-        let mut error = String::new();
-        if &space > &&(8u8) {
-            error = format!("Error: {} is not valid.\n", space);
-        } else if self.game.board.is_space_played(&space) {
-            error = format!("Error: Can't play in position {}, as it has been already played.\n", space);
+    pub fn get_display_marker(marker: &PlayMarkers) -> &'static str {
+        if marker == &PlayMarkers::X { "X" } else { "O" }
+    }
+
+    pub fn to_record(&self) -> GameRecord {
+        let mut record = self.game.to_record();
+        record.set_tag(MODE_TAG, self.mode.to_possible_value().unwrap().get_name());
+        if self.mode != GameMode::Local {
+            record.set_tag(DIFFICULTY_TAG, self.difficulty.to_possible_value().unwrap().get_name());
         }
-        self.error = Some(error);
+        record.set_tag("X", self.get_player_name(&PlayMarkers::X));
+        record.set_tag("O", self.get_player_name(&PlayMarkers::O));
+        record
     }
 
-    fn get_display_marker(&self, marker: &PlayMarkers) -> &str {
-        if marker == &PlayMarkers::X { "X" } else { "O" }
+    // Takes back the last move, and in ai modes the ai's reply to it as well.
+    fn undo(&mut self) {
+        let plies = if self.mode == GameMode::Local { 1 } else { 2 };
+        if self.game.history().len() < plies {
+            self.error = Some("Error: Nothing to undo.\n".to_string());
+            return;
+        }
+        for _ in 0..plies {
+            self.game.undo();
+        }
     }
 
-    fn ai_play(&mut self) {
-        self.game.play(get_best_move(&self.game));
+    // Lists every move with where it leads, best first.
+    fn hint(&mut self) {
+        let size = self.game.get_board().get_size();
+        let scored_moves: Vec<String> = ai2::get_scored_moves(&self.game).iter()
+            .map(|scored| format!("{}: {}", self.notation.format(scored.space, size), scored.result))
+            .collect();
+        self.message = Some(format!("Hint: {}\n", scored_moves.join(", ")));
+    }
+
+    fn get_match_winner(&self) -> Option<PlayMarkers> {
+        self.resigned.map(|marker| marker.get_opponent()).or_else(|| self.game.get_winner())
+    }
+
+    fn swap_sides(&mut self) {
+        self.mode = self.mode.swapped();
+        self.player_one = self.player_one.get_opponent();
+        self.game = Game::new(self.game.get_board().get_size(), self.game.get_win_length());
+        self.resigned = None;
+        self.error = None;
+        self.message = None;
+    }
+
+    fn new_game(&mut self) {
+        self.game = Game::new(self.game.get_board().get_size(), self.game.get_win_length());
+        self.resigned = None;
+        if self.is_ai_turn() {
+            self.ai_play();
+        }
+        self.message = Some("New game started.\n".to_string());
+    }
+
+    fn save(&mut self, path: &str) {
+        match fs::write(path, self.to_record().to_string()) {
+            Ok(()) => {
+                self.error = None;
+                self.message = Some(format!("Game saved to {}.\n", path));
+            }
+            Err(error) => {
+                self.error = Some(format!("Error: Can't save game to {}: {}.\n", path, error));
+            }
+        }
+    }
+
+    fn get_player_name(&self, marker: &PlayMarkers) -> &'static str {
+        if self.mode.is_ai(marker) { "ai" } else { "human" }
+    }
+
+    fn is_ai_turn(&self) -> bool {
+        !self.game.is_over() && self.mode.is_ai(&self.game.get_active_marker())
     }
-}
 
-pub trait PlayManager {
-    fn play(&mut self, space: u8);
+    fn ai_play(&mut self) {
+        let space = self.ai.choose_move(&self.game);
+        self.game.play(space).expect("AI picked an unavailable space.");
+    }
 }
 
 #[cfg(test)]
 mod cli_game {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
     use crate::cli_game_manager::{CLIGameManager, GameMode};
+    use crate::difficulty::Difficulty;
+    use crate::game::Game;
+    use crate::notation::MoveNotation;
 
     #[test]
     fn prints_welcome() {
-        let cli = CLIGameManager::new(GameMode::LOCAL);
+        let cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         let mut output = Vec::new();
         cli.print(&mut output);
         assert_eq!(output, b"_|_|_\n_|_|_\n_|_|_\nX's turn!\nAvailable spaces in order from left to right and top to bottom: 0, 1, 2, 3, 4, 5, 6, 7, 8.\nEnter number: \n\n");
@@ -175,7 +356,7 @@ mod cli_game {
 
     #[test]
     fn prints_after_play() {
-        let mut cli = CLIGameManager::new(GameMode::LOCAL);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         let input = b"0";
         cli.input_play(&input[..]);
         let mut output = Vec::new();
@@ -185,7 +366,7 @@ mod cli_game {
 
     #[test]
     fn should_print_error_on_invalid_input() {
-        let mut cli = CLIGameManager::new(GameMode::LOCAL);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         let input = b"-1";
         cli.input_play(&input[..]);
         let mut output = Vec::new();
@@ -195,7 +376,7 @@ mod cli_game {
 
     #[test]
     fn should_print_error_on_out_of_range_input() {
-        let mut cli = CLIGameManager::new(GameMode::LOCAL);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         let input = b"9\n";
         cli.input_play(&input[..]);
         let mut output = Vec::new();
@@ -205,7 +386,7 @@ mod cli_game {
 
     #[test]
     fn should_print_error_on_playing_occupied_space() {
-        let mut cli = CLIGameManager::new(GameMode::LOCAL);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         let input = b"0";
         cli.input_play(&input[..]);
         let input = b"0";
@@ -217,26 +398,230 @@ mod cli_game {
 
     #[test]
     fn should_print_winner() {
-        let mut cli = CLIGameManager::new(GameMode::LOCAL);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         for play in ["0", "3", "1", "4", "2"] {
             let input = play.as_bytes();
-            cli.input_play(&input[..]);
+            cli.input_play(input);
+        }
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert_eq!(output, b"X|X|X\no|o|_\n_|_|_\nX Wins!\n\n");
+    }
+
+    #[test]
+    fn should_print_both_completed_lines() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        for play in ["0", "3", "1", "5", "4", "7", "6", "8", "2"] {
+            cli.input_play(play.as_bytes());
         }
         let mut output = Vec::new();
         cli.print(&mut output);
-        assert_eq!(output, b"X|X|X\nO|O|_\n_|_|_\nX Wins!\n\n");
+        assert_eq!(output, b"X|X|X\no|X|o\nX|o|o\nX Wins!\n\n");
     }
 
     #[test]
     fn should_print_game_over() {
-        let mut cli = CLIGameManager::new(GameMode::LOCAL);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
         for play in ["0", "4", "2", "1", "7", "5", "3", "6", "8"] {
             let input = play.as_bytes();
-            cli.input_play(&input[..]);
+            cli.input_play(input);
         }
         let mut output = Vec::new();
         cli.print(&mut output);
         assert_eq!(output, b"X|O|X\nX|O|O\nO|X|X\nGame Over!\n\n");
     }
-}
 
+    #[test]
+    fn clears_error_after_valid_play() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b"9"[..]);
+        cli.input_play(&b"0"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert_eq!(output, b"X|_|_\n_|_|_\n_|_|_\nO's turn!\nAvailable spaces in order from left to right and top to bottom: 1, 2, 3, 4, 5, 6, 7, 8.\nEnter number: \n\n");
+    }
+
+    #[test]
+    fn ai_answers_human_play() {
+        let mut cli = CLIGameManager::new(GameMode::AiLast, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b"0"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert_eq!(output, b"X|_|_\n_|O|_\n_|_|_\nX's turn!\nAvailable spaces in order from left to right and top to bottom: 1, 2, 3, 5, 6, 7, 8.\nEnter number: \n\n");
+    }
+
+    #[test]
+    fn same_seed_replays_session() {
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            let mut cli = CLIGameManager::new(GameMode::AiFirst, Game::new(3, 3), Difficulty::Easy, StdRng::seed_from_u64(42));
+            let mut output = Vec::new();
+            cli.start(&b"0\n1\n2\n3\n4\n5\n6\n7\n8\n"[..], &mut output);
+            outputs.push(output);
+        }
+        assert_eq!(outputs[0], outputs[1]);
+    }
+
+    #[test]
+    fn prints_larger_board() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(4, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b"5"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert_eq!(output, b"_|_|_|_\n_|X|_|_\n_|_|_|_\n_|_|_|_\nO's turn!\nAvailable spaces in order from left to right and top to bottom: 0, 1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15.\nEnter number: \n\n");
+    }
+
+    #[test]
+    fn saves_game_record() {
+        let path = std::env::temp_dir().join("rust-tic-tac-toe-saves-game-record.txt");
+        let mut cli = CLIGameManager::new(GameMode::AiLast, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b"0"[..]);
+        cli.input_play(format!(":save {}", path.display()).as_bytes());
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, "[Size \"3\"]\n[WinLength \"3\"]\n[Result \"*\"]\n[Mode \"ai-last\"]\n[Difficulty \"hard\"]\n[X \"human\"]\n[O \"ai\"]\n\n0 4\n");
+        assert!(String::from_utf8(output).unwrap().ends_with(&format!("Game saved to {}.\nEnter number: \n\n", path.display())));
+    }
+
+    #[test]
+    fn should_print_error_on_failed_save() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b":save /nonexistent-directory/game.txt"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert!(String::from_utf8(output).unwrap().contains("Error: Can't save game to /nonexistent-directory/game.txt: "));
+    }
+
+    #[test]
+    fn ai_moves_first_when_resuming_on_its_turn() {
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
+        let mut cli = CLIGameManager::new(GameMode::AiLast, game, Difficulty::Hard, StdRng::seed_from_u64(0));
+        let mut output = Vec::new();
+        cli.start(&b"0\n1\n2\n3\n4\n5\n6\n7\n8\n"[..], &mut output);
+        assert!(String::from_utf8(output).unwrap().starts_with("X|_|_\n_|O|_\n_|_|_\nX's turn!"));
+    }
+
+    #[test]
+    fn accepts_coordinate_notation() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0))
+            .with_notation(MoveNotation::Coordinate);
+        cli.input_play(&b"b2"[..]);
+        cli.input_play(&b"B2"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert_eq!(output, b"_|_|_\n_|X|_\n_|_|_\nO's turn!\nAvailable spaces in order from left to right and top to bottom: a1, b1, c1, a2, c2, a3, b3, c3.\nError: Can't play in position b2, as it has been already played.\nEnter coordinate: \n\n");
+    }
+
+    #[test]
+    fn accepts_row_column_and_numpad_notation() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0))
+            .with_notation(MoveNotation::RowColumn);
+        cli.input_play(&b"1,3"[..]);
+        cli.input_play(&b"b2"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert!(String::from_utf8(output).unwrap().ends_with("1,1, 1,2, 2,1, 2,2, 2,3, 3,1, 3,2, 3,3.\nError: b2 is not valid.\nEnter row,column: \n\n"));
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0))
+            .with_notation(MoveNotation::Numpad);
+        cli.input_play(&b"7"[..]);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert!(String::from_utf8(output).unwrap().starts_with("X|_|_\n_|_|_\n_|_|_\nO's turn!\nAvailable spaces in order from left to right and top to bottom: 8, 9, 4, 5, 6, 1, 2, 3.\n"));
+    }
+
+    #[test]
+    fn undoes_human_and_ai_moves() {
+        let mut cli = CLIGameManager::new(GameMode::AiLast, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b":undo"[..]);
+        assert_eq!(cli.error, Some("Error: Nothing to undo.\n".to_string()));
+        cli.input_play(&b"0"[..]);
+        cli.input_play(&b":undo"[..]);
+        assert!(cli.game.history().is_empty());
+        assert_eq!(cli.error, None);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b"0"[..]);
+        cli.input_play(&b"1"[..]);
+        cli.input_play(&b":undo"[..]);
+        assert_eq!(cli.game.history(), &[0]);
+    }
+
+    #[test]
+    fn hints_winning_move() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0))
+            .with_notation(MoveNotation::Coordinate);
+        for play in ["a1", "a2", "b1", "b2"] {
+            cli.input_play(play.as_bytes());
+        }
+        cli.input_play(&b":hint"[..]);
+        assert_eq!(cli.message, Some("Hint: c1: win in 1, c2: draw, a3: lose in 2, b3: lose in 2, c3: lose in 2\n".to_string()));
+    }
+
+    #[test]
+    fn resigning_ends_game() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        let mut output = Vec::new();
+        cli.start(&b"4\n:resign\n0\n"[..], &mut output);
+        assert!(String::from_utf8(output).unwrap().ends_with("_|_|_\n_|X|_\n_|_|_\nO resigns. X Wins!\n\n"));
+    }
+
+    #[test]
+    fn starts_new_game() {
+        let mut cli = CLIGameManager::new(GameMode::AiFirst, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        let mut output = Vec::new();
+        cli.start(&b"1\n:resign\n"[..], &mut output);
+        cli.input_play(&b":new"[..]);
+        assert!(!cli.is_finished());
+        assert_eq!(cli.game.history().len(), 1);
+        assert_eq!(cli.message, Some("New game started.\n".to_string()));
+    }
+
+    #[test]
+    fn reports_command_errors_and_help() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.input_play(&b":save"[..]);
+        assert_eq!(cli.error, Some("Error: Usage: :save <file>\n".to_string()));
+        cli.input_play(&b":help"[..]);
+        assert_eq!(cli.error, None);
+        let mut output = Vec::new();
+        cli.print(&mut output);
+        assert!(String::from_utf8(output).unwrap().contains("Commands:\n  :undo "));
+    }
+
+    #[test]
+    fn quits_on_command_or_end_of_input() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        let mut output = Vec::new();
+        cli.start(&b"0\n:quit\n1\n"[..], &mut output);
+        assert_eq!(cli.game.history(), &[0]);
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        cli.start(&b"0\n"[..], &mut output);
+        assert!(cli.is_finished());
+    }
+
+    #[test]
+    fn match_swaps_sides_and_keeps_score() {
+        let mut cli = CLIGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        let mut output = Vec::new();
+        cli.start_match(3, &b"0\n3\n1\n4\n2\n0\n3\n1\n4\n2\n"[..], &mut output);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Game 1 of 3\n\n_|_|_\n"));
+        assert!(output.contains("Score after 1 game:\n  Player 1: 1 wins as X, 0 wins as O, 0 draws\n  Player 2: 0 wins as X, 0 wins as O, 0 draws\n"));
+        assert!(output.contains("Score after 2 games:\n  Player 1: 1 wins as X, 0 wins as O, 0 draws\n  Player 2: 1 wins as X, 0 wins as O, 0 draws\n"));
+        assert!(output.ends_with("Match over! The match is drawn 1-1.\n"));
+    }
+
+    #[test]
+    fn match_ends_once_decided() {
+        let mut cli = CLIGameManager::new(GameMode::AiLast, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        let mut output = Vec::new();
+        cli.start_match(3, &b":resign\n:resign\n0\n"[..], &mut output);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Game 2 of 3\n\n"));
+        assert!(!output.contains("Game 3 of 3"));
+        assert!(output.contains("  AI: 0 wins as X, 1 wins as O, 0 draws\n"));
+        assert!(output.ends_with("  AI: 1 wins as X, 1 wins as O, 0 draws\n\nMatch over! AI wins the match 2-0.\n"));
+    }
+}
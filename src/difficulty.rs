@@ -0,0 +1,72 @@
+use rand::rngs::StdRng;
+use crate::ai2::Ai2Player;
+use crate::player::Player;
+use crate::random_player::RandomPlayer;
+
+// Medium looks two moves ahead, enough to take or block an immediate win, and blunders now and then.
+const MEDIUM_SEARCH_DEPTH: u8 = 2;
+const MEDIUM_BLUNDER_CHANCE: f64 = 0.2;
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn get_player(&self, rng: StdRng) -> Box<dyn Player> {
+        match self {
+            Difficulty::Easy => Box::new(RandomPlayer::new(rng)),
+            Difficulty::Medium => Box::new(Ai2Player::limited(MEDIUM_SEARCH_DEPTH, MEDIUM_BLUNDER_CHANCE, rng)),
+            Difficulty::Hard => Box::new(Ai2Player::new(rng)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod difficulty_levels {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::difficulty::Difficulty;
+    use crate::game::Game;
+    use crate::play_markers::PlayMarkers;
+    use crate::player::play_game;
+
+    #[test]
+    fn hard_never_loses_to_easy() {
+        for seed in 0..5 {
+            let mut game = Game::new(3, 3);
+            let mut easy = Difficulty::Easy.get_player(StdRng::seed_from_u64(seed));
+            let mut hard = Difficulty::Hard.get_player(StdRng::seed_from_u64(seed + 100));
+            let winner = play_game(&mut game, easy.as_mut(), hard.as_mut());
+            assert_ne!(winner, Some(PlayMarkers::X));
+        }
+    }
+
+    #[test]
+    fn every_level_finishes_a_game() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let mut game = Game::new(3, 3);
+            let mut x_player = difficulty.get_player(StdRng::seed_from_u64(0));
+            let mut o_player = Difficulty::Medium.get_player(StdRng::seed_from_u64(1));
+            play_game(&mut game, x_player.as_mut(), o_player.as_mut());
+            assert!(game.is_over());
+        }
+    }
+
+    #[test]
+    fn same_seed_plays_same_game() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let mut histories = Vec::new();
+            for _ in 0..2 {
+                let mut game = Game::new(3, 3);
+                let mut x_player = difficulty.get_player(StdRng::seed_from_u64(7));
+                let mut o_player = Difficulty::Easy.get_player(StdRng::seed_from_u64(8));
+                play_game(&mut game, x_player.as_mut(), o_player.as_mut());
+                histories.push(game.history().to_vec());
+            }
+            assert_eq!(histories[0], histories[1]);
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use crate::ai2;
+use crate::board::MAX_SIZE;
+use crate::game::Game;
+
+// Line based protocol for driving the ai from other programs, answering each command with one line:
+//
+// boardsize <size> [<win length>]  -> ok         (starts a new game on the new board)
+// newgame                          -> ok
+// position [moves <space> ...]     -> ok         (the moves are played from an empty board)
+// go [<milliseconds>]              -> bestmove <space>
+// quit                                           (no answer, the engine stops)
+//
+// Anything that can't be carried out is answered with "error <reason>".
+pub struct EngineProtocol {
+    game: Game,
+    rng: StdRng,
+}
+
+impl EngineProtocol {
+    pub fn new(size: u8, win_length: u8, rng: StdRng) -> Self {
+        Self {
+            game: Game::new(size, win_length),
+            rng,
+        }
+    }
+
+    // Runs until quit, the end of the input or a read error. Lines that aren't UTF-8 are answered with an
+    // error like any other bad command.
+    pub fn start<R, W>(&mut self, mut reader: R, mut writer: W) where R: BufRead, W: Write {
+        let mut bytes = Vec::new();
+        loop {
+            bytes.clear();
+            match reader.read_until(b'\n', &mut bytes) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let reply = match std::str::from_utf8(&bytes) {
+                Ok(line) => match line.trim() {
+                    "quit" => break,
+                    "" => continue,
+                    command => self.handle_command(command),
+                },
+                Err(_) => Err("Input is not valid UTF-8.".to_string()),
+            };
+            let reply = reply.unwrap_or_else(|error| format!("error {}", error));
+            let _result = writeln!(writer, "{}", reply);
+            let _result = writer.flush();
+        }
+    }
+
+    pub fn handle_command(&mut self, command: &str) -> Result<String, String> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arguments: Vec<&str> = words.collect();
+        match name {
+            "boardsize" => self.set_board_size(&arguments),
+            "newgame" if arguments.is_empty() => {
+                self.game = Game::new(self.game.get_board().get_size(), self.game.get_win_length());
+                Ok("ok".to_string())
+            }
+            "position" => self.set_position(&arguments),
+            "go" => self.go(&arguments),
+            _ => Err(format!("Unknown command \"{}\".", command)),
+        }
+    }
+
+    fn set_board_size(&mut self, arguments: &[&str]) -> Result<String, String> {
+        let numbers: Vec<u8> = arguments.iter().map(|argument| argument.parse()).collect::<Result<_, _>>()
+            .map_err(|_| "Usage: boardsize <size> [<win length>]".to_string())?;
+        let (size, win_length) = match numbers[..] {
+            [size] => (size, self.game.get_win_length().min(size)),
+            [size, win_length] => (size, win_length),
+            _ => return Err("Usage: boardsize <size> [<win length>]".to_string()),
+        };
+        if !(1..=MAX_SIZE).contains(&size) {
+            return Err(format!("Size must be between 1 and {}.", MAX_SIZE));
+        }
+        if !(1..=size).contains(&win_length) {
+            return Err("Win length must be between 1 and the board size.".to_string());
+        }
+        self.game = Game::new(size, win_length);
+        Ok("ok".to_string())
+    }
+
+    // Leaves the current game untouched when any of the moves can't be played.
+    fn set_position(&mut self, arguments: &[&str]) -> Result<String, String> {
+        let moves = match arguments.split_first() {
+            None => &[][..],
+            Some((&"moves", moves)) => moves,
+            Some(_) => return Err("Usage: position [moves <space> ...]".to_string()),
+        };
+        let mut game = Game::new(self.game.get_board().get_size(), self.game.get_win_length());
+        for space in moves {
+            let space = space.parse::<u8>().map_err(|_| format!("{} is not a space.", space))?;
+            game.play(space).map_err(|error| format!("Can't play {}: {}", space, error))?;
+        }
+        self.game = game;
+        Ok("ok".to_string())
+    }
+
+    fn go(&mut self, arguments: &[&str]) -> Result<String, String> {
+        let time_limit = match arguments {
+            [] => None,
+            [milliseconds] => Some(Duration::from_millis(milliseconds.parse().map_err(|_| "Usage: go [<milliseconds>]".to_string())?)),
+            _ => return Err("Usage: go [<milliseconds>]".to_string()),
+        };
+        if self.game.is_over() {
+            return Err("The game is already over.".to_string());
+        }
+        Ok(format!("bestmove {}", self.get_best_move(time_limit)))
+    }
+
+    // With a time limit, searches one move deeper at a time and answers with the deepest search that
+    // finished when the time runs out.
+    fn get_best_move(&mut self, time_limit: Option<Duration>) -> u8 {
+        match time_limit {
+            Some(time_limit) => ai2::get_best_move_before(&self.game, Instant::now() + time_limit, &mut self.rng),
+            None => ai2::get_best_move_to_depth(&self.game, u8::MAX, &mut self.rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod engine_commands {
+    use rand::SeedableRng;
+    use std::time::{Duration, Instant};
+    use rand::rngs::StdRng;
+    use crate::engine_protocol::EngineProtocol;
+
+    fn engine() -> EngineProtocol {
+        EngineProtocol::new(3, 3, StdRng::seed_from_u64(0))
+    }
+
+    #[test]
+    fn answers_commands_until_quit() {
+        let mut output = Vec::new();
+        engine().start(&b"newgame\n\nposition moves 0 4 2\ngo\nquit\ngo\n"[..], &mut output);
+        assert_eq!(String::from_utf8(output).unwrap(), "ok\nok\nbestmove 1\n");
+    }
+
+    #[test]
+    fn finds_win_with_time_limit() {
+        let mut engine = engine();
+        engine.handle_command("position moves 0 3 1 4").unwrap();
+        assert_eq!(engine.handle_command("go 1000"), Ok("bestmove 2".to_string()));
+    }
+
+    #[test]
+    fn answers_within_time_limit_on_large_board() {
+        let mut engine = engine();
+        engine.handle_command("boardsize 15 5").unwrap();
+        engine.handle_command("position moves 112 96 113 98 128").unwrap();
+        let started = Instant::now();
+        let reply = engine.handle_command("go 100").unwrap();
+        // Loose enough for a debug build on a busy machine; the deadline itself is tested in ai2.
+        assert!(started.elapsed() < Duration::from_millis(500), "took {:?}", started.elapsed());
+        let space: u8 = reply.strip_prefix("bestmove ").unwrap().parse().unwrap();
+        assert!(engine.game.is_valid_move(&space));
+    }
+
+    #[test]
+    fn changes_board_size() {
+        let mut engine = engine();
+        assert_eq!(engine.handle_command("boardsize 15 5"), Ok("ok".to_string()));
+        assert_eq!(engine.handle_command("position moves 200"), Ok("ok".to_string()));
+        assert_eq!(engine.handle_command("boardsize 4"), Ok("ok".to_string()));
+        assert_eq!(engine.game.get_win_length(), 4);
+        assert!(engine.game.history().is_empty());
+        assert_eq!(engine.handle_command("boardsize 16").unwrap_err(), "Size must be between 1 and 15.");
+        assert_eq!(engine.handle_command("boardsize 3 4").unwrap_err(), "Win length must be between 1 and the board size.");
+    }
+
+    #[test]
+    fn rejects_invalid_positions() {
+        let mut engine = engine();
+        engine.handle_command("position moves 4").unwrap();
+        assert_eq!(engine.handle_command("position moves 0 0").unwrap_err(), "Can't play 0: Can't play in position 0, as it has been already played.");
+        assert_eq!(engine.handle_command("position moves a1").unwrap_err(), "a1 is not a space.");
+        assert_eq!(engine.game.history(), &[4]);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut engine = engine();
+        engine.handle_command("position moves 0 3 1 4 2").unwrap();
+        assert_eq!(engine.handle_command("go").unwrap_err(), "The game is already over.");
+        assert_eq!(engine.handle_command("go soon").unwrap_err(), "Usage: go [<milliseconds>]");
+        assert_eq!(engine.handle_command("fly").unwrap_err(), "Unknown command \"fly\".");
+        let mut output = Vec::new();
+        engine.start(&b"position moves 0 3 1 4\n\xff\xfe\ngo\n"[..], &mut output);
+        assert_eq!(String::from_utf8(output).unwrap(), "ok\nerror Input is not valid UTF-8.\nbestmove 2\n");
+    }
+}
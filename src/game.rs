@@ -1,52 +1,225 @@
-use std::collections::HashMap;
-use crate::board::Board;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use crate::bit_mask::BitMask;
+use crate::board::{Board, MAX_SIZE};
+use crate::game_record::{GameRecord, RecordError, POSITION_TAG, RESULT_TAG, SIZE_TAG, WIN_LENGTH_TAG};
+use crate::move_outcome::{MoveError, MoveOutcome};
 use crate::play_markers::PlayMarkers;
+use crate::position_error::PositionError;
+use crate::position_notation::{self, NotationError};
 use crate::winning_plays;
 
-
+// Serialized as {"size": 3, "win_length": 3, "moves": [0, 4], "winner": null, "is_over": false}, with a "position"
+// in notation when the moves start from a set up board. The board is rebuilt from the moves when deserializing,
+// and the winner and is_over have to agree with it.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "GameData", try_from = "GameData"))]
 pub struct Game {
-    pub(crate) board: Board,
-    pub(crate) is_over: bool,
+    board: Board,
+    is_over: bool,
     winner: Option<PlayMarkers>,
-    winning_plays: HashMap<u8, Vec<[u8; 3]>>,
+    // Lines the winning move completed; one move can finish more than one.
+    completed_lines: Vec<BitMask>,
+    win_length: u8,
+    winning_plays: Arc<Vec<Vec<BitMask>>>,
+    winning_lines: Arc<Vec<BitMask>>,
+    history: Vec<u8>,
+    undone_moves: Vec<u8>,
 }
 
 impl Game {
-    pub fn new() -> Self {
+    pub fn new(size: u8, win_length: u8) -> Self {
+        assert!((1..=size).contains(&win_length), "Win length must be between 1 and the board size.");
         Self {
-            board: Board::new(),
+            board: Board::new(size),
             is_over: false,
             winner: None,
-            winning_plays: winning_plays::get_winning_plays(),
+            completed_lines: Vec::new(),
+            win_length,
+            winning_plays: Arc::new(winning_plays::get_winning_plays(size, win_length)),
+            winning_lines: Arc::new(winning_plays::get_winning_line_masks(size, win_length)),
+            history: Vec::new(),
+            undone_moves: Vec::new(),
         }
     }
 
     pub fn copy(&self) -> Self {
-        Self {
-            board: self.board.copy(),
-            is_over: self.is_over,
-            winner: self.winner,
-            winning_plays: winning_plays::get_winning_plays(),
+        self.clone()
+    }
+
+    // Sets up a position without a move history, rejecting boards that play can't reach.
+    pub fn from_board(board: Board, win_length: u8) -> Result<Self, PositionError> {
+        if !(1..=board.get_size()).contains(&win_length) {
+            return Err(PositionError::InvalidWinLength(win_length, board.get_size()));
         }
+        let mut game = Game::new(board.get_size(), win_length);
+        game.board = board;
+        game.validate()?;
+        for marker in [PlayMarkers::X, PlayMarkers::O] {
+            let lines = game.get_lines_owned_by(&marker);
+            if !lines.is_empty() {
+                game.winner = Some(marker);
+                game.completed_lines = lines;
+            }
+        }
+        game.is_over = game.winner.is_some() || game.board.is_full();
+        Ok(game)
     }
 
+    // Checks the board could have been reached by X and O taking turns, with no moves after a win.
+    pub fn validate(&self) -> Result<(), PositionError> {
+        let x_count = self.board.get_marker_spaces(&PlayMarkers::X).count() as u8;
+        let o_count = self.board.get_marker_spaces(&PlayMarkers::O).count() as u8;
+        if x_count != o_count && x_count != o_count + 1 {
+            return Err(PositionError::MarkerCount(x_count, o_count));
+        }
+        let x_lines = self.get_lines_owned_by(&PlayMarkers::X);
+        let o_lines = self.get_lines_owned_by(&PlayMarkers::O);
+        if !x_lines.is_empty() && !o_lines.is_empty() {
+            return Err(PositionError::BothWon);
+        }
+        for (marker, lines) in [(PlayMarkers::X, x_lines), (PlayMarkers::O, o_lines)] {
+            if lines.is_empty() {
+                continue;
+            }
+            // The winner moved last, so it is the other side's turn.
+            if self.get_active_marker() == marker {
+                return Err(PositionError::WrongWinner(marker));
+            }
+            // Every line the winning move completed passes through the space it was played in.
+            if lines.iter().fold(lines[0], |common, line| common & *line) == BitMask::EMPTY {
+                return Err(PositionError::PlayAfterWin(marker));
+            }
+        }
+        Ok(())
+    }
 
-    pub fn play(&mut self, space: u8) {
-        let active_marker = self.get_active_marker();
-        if self.is_valid_move(&space) {
-            self.board.play(space, &active_marker);
+    // Sets up a position without a move history, e.g. "XO_/_X_/__O x" (see position_notation).
+    pub fn from_notation(notation: &str) -> Result<Self, NotationError> {
+        let (board, side, win_length) = position_notation::parse_position(notation)?;
+        let game = Game::from_board(board, win_length).map_err(NotationError::InvalidPosition)?;
+        if game.get_active_marker() != side {
+            return Err(NotationError::WrongSideToMove(game.get_active_marker()));
         }
-        if self.board.is_full() {
-            self.is_over = true;
+        Ok(game)
+    }
+
+    pub fn to_notation(&self) -> String {
+        position_notation::format_position(&self.board, self.win_length)
+    }
+
+    pub fn from_record(record: &GameRecord) -> Result<Self, RecordError> {
+        let size = record.get_number_tag(SIZE_TAG, 1..=MAX_SIZE)?;
+        let win_length = record.get_number_tag(WIN_LENGTH_TAG, 1..=size)?;
+        let mut game = match record.get_tag(POSITION_TAG) {
+            Some(position) => {
+                let game = Game::from_notation(position).map_err(RecordError::InvalidPosition)?;
+                if game.board.get_size() != size || game.win_length != win_length {
+                    return Err(RecordError::InvalidTag(POSITION_TAG.to_string(), position.to_string()));
+                }
+                game
+            }
+            None => Game::new(size, win_length),
+        };
+        for space in record.get_moves() {
+            game.play(*space).map_err(|error| RecordError::InvalidMove(*space, error))?;
+        }
+        match record.get_tag(RESULT_TAG) {
+            Some(result) if result != game.get_result_tag() => {
+                Err(RecordError::ResultMismatch(result.to_string(), game.get_result_tag().to_string()))
+            }
+            _ => Ok(game),
+        }
+    }
+
+    pub fn to_record(&self) -> GameRecord {
+        let mut record = GameRecord::new(self.history.clone());
+        record.set_tag(SIZE_TAG, &self.board.get_size().to_string());
+        record.set_tag(WIN_LENGTH_TAG, &self.win_length.to_string());
+        if let Some(position) = self.get_start_position() {
+            record.set_tag(POSITION_TAG, &position);
+        }
+        record.set_tag(RESULT_TAG, self.get_result_tag());
+        record
+    }
+
+    // Notation of the position the moves in the history start from, unless that is an empty board.
+    pub fn get_start_position(&self) -> Option<String> {
+        let mut board = self.board;
+        for space in &self.history {
+            board.clear(*space);
         }
-        if self.is_winning_play(&space, &active_marker) {
+        if board.get_played_count() == 0 {
+            None
+        } else {
+            Some(position_notation::format_position(&board, self.win_length))
+        }
+    }
+
+
+    pub fn play(&mut self, space: u8) -> Result<MoveOutcome, MoveError> {
+        self.check_move(&space)?;
+        self.undone_moves.clear();
+        Ok(self.make_move(space))
+    }
+
+    // Takes back the last move, returning the space it was played in.
+    pub fn undo(&mut self) -> Option<u8> {
+        let space = self.history.pop()?;
+        self.board.clear(space);
+        // No move can follow the end of a game, so every earlier position was still in play.
+        self.is_over = false;
+        self.winner = None;
+        self.completed_lines.clear();
+        self.undone_moves.push(space);
+        Some(space)
+    }
+
+    // Plays the last undone move again, returning the space it was played in.
+    pub fn redo(&mut self) -> Option<u8> {
+        let space = self.undone_moves.pop()?;
+        self.make_move(space);
+        Some(space)
+    }
+
+    pub fn history(&self) -> &[u8] {
+        &self.history
+    }
+
+    pub fn last_move(&self) -> Option<u8> {
+        self.history.last().copied()
+    }
+
+    fn make_move(&mut self, space: u8) -> MoveOutcome {
+        let active_marker = self.get_active_marker();
+        self.board.play(space, &active_marker);
+        self.history.push(space);
+        let marker_spaces = self.board.get_marker_spaces(&active_marker);
+        self.completed_lines.extend(self.winning_plays[space as usize].iter().filter(|line| marker_spaces.contains_all(line)));
+        if !self.completed_lines.is_empty() {
             self.winner = Some(active_marker);
             self.is_over = true;
+            MoveOutcome::Won(active_marker)
+        } else if self.board.is_full() {
+            self.is_over = true;
+            MoveOutcome::Draw
+        } else {
+            MoveOutcome::Continue
         }
     }
 
+    pub fn get_board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_over
+    }
+
     pub fn get_active_marker(&self) -> PlayMarkers {
-        if self.board.spaces.len() % 2 == 0 {
+        if self.board.get_played_count().is_multiple_of(2) {
             PlayMarkers::X
         } else {
             PlayMarkers::O
@@ -57,103 +230,197 @@ impl Game {
         self.winner
     }
 
+    pub fn get_completed_lines(&self) -> &[BitMask] {
+        &self.completed_lines
+    }
+
+    pub fn is_in_completed_line(&self, space: u8) -> bool {
+        self.completed_lines.iter().any(|line| line.contains(space))
+    }
+
+    pub fn get_win_length(&self) -> u8 {
+        self.win_length
+    }
+
+    // "X" or "O" for the winner, "draw" for a cats game and "*" while still in play.
+    pub fn get_result_tag(&self) -> &'static str {
+        match self.winner {
+            Some(PlayMarkers::X) => "X",
+            Some(PlayMarkers::O) => "O",
+            None if self.is_over => "draw",
+            None => "*",
+        }
+    }
+
+    pub fn get_winning_lines(&self) -> &[BitMask] {
+        &self.winning_lines
+    }
+
+    fn get_lines_owned_by(&self, marker: &PlayMarkers) -> Vec<BitMask> {
+        let marker_spaces = self.board.get_marker_spaces(marker);
+        self.winning_lines.iter().filter(|line| marker_spaces.contains_all(line)).copied().collect()
+    }
+
+    pub fn get_winning_lines_through(&self, space: &u8) -> &[BitMask] {
+        match self.winning_plays.get(*space as usize) {
+            Some(winning_plays) => winning_plays,
+            None => &[],
+        }
+    }
+
     pub fn get_available_plays(&self) -> Vec<u8> {
         let mut plays = Vec::new();
-        for p in 0u8..9u8 {
+        for p in 0u8..self.board.get_space_count() {
             if self.is_valid_move(&p) { plays.push(p); }
         }
         plays
     }
 
     pub fn is_valid_move(&self, space: &u8) -> bool {
-        if !self.is_over && !self.board.is_space_played(space) && &space < &&9u8 { true } else { false }
+        self.check_move(space).is_ok()
     }
 
-    fn is_winning_play(&self, space: &u8, marker: &PlayMarkers) -> bool {
-        let mut is_position_winning_play = false;
-        'win_loop: for winning_plays in self.winning_plays.get(&space) {
-            for winning_spaces in winning_plays {
-                if self.is_marker_in_all_positions(winning_spaces, marker) {
-                    is_position_winning_play = true;
-                    break 'win_loop;
-                }
-            }
+    pub fn check_move(&self, space: &u8) -> Result<(), MoveError> {
+        if self.is_over {
+            Err(MoveError::GameOver)
+        } else if *space >= self.board.get_space_count() {
+            Err(MoveError::OutOfRange(*space))
+        } else if self.board.is_space_played(space) {
+            Err(MoveError::Occupied(*space))
+        } else {
+            Ok(())
         }
-        is_position_winning_play
     }
+}
 
-    fn is_marker_in_all_positions(&self, winning_play: &[u8; 3], marker: &PlayMarkers) -> bool {
-        let mut did_play_all_position = true;
-        for space in winning_play {
-            if self.board.get_space_marker(space) != Some(marker) {
-                did_play_all_position = false;
-                break;
-            }
-        }
-        did_play_all_position
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_notation())
     }
+}
 
+impl FromStr for Game {
+    type Err = NotationError;
 
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Game::from_notation(s)
+    }
+}
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GameData {
+    size: u8,
+    win_length: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<String>,
+    moves: Vec<u8>,
+    winner: Option<PlayMarkers>,
+    is_over: bool,
+}
+
+#[cfg(feature = "serde")]
+impl From<Game> for GameData {
+    fn from(game: Game) -> Self {
+        Self {
+            size: game.board.get_size(),
+            win_length: game.win_length,
+            position: game.get_start_position(),
+            moves: game.history,
+            winner: game.winner,
+            is_over: game.is_over,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GameData> for Game {
+    type Error = String;
+
+    fn try_from(data: GameData) -> Result<Self, Self::Error> {
+        if !(1..=MAX_SIZE).contains(&data.size) || !(1..=data.size).contains(&data.win_length) {
+            return Err(format!("Invalid size {} with win length {}.", data.size, data.win_length));
+        }
+        let mut game = match &data.position {
+            Some(position) => Game::from_notation(position).map_err(|error| error.to_string())?,
+            None => Game::new(data.size, data.win_length),
+        };
+        if game.board.get_size() != data.size || game.win_length != data.win_length {
+            return Err("Position doesn't match the size and win length.".to_string());
+        }
+        for space in data.moves {
+            game.play(space).map_err(|error| format!("Can't replay move {}: {}", space, error))?;
+        }
+        if game.winner != data.winner || game.is_over != data.is_over {
+            return Err("Winner and is_over don't match the moves.".to_string());
+        }
+        Ok(game)
+    }
 }
 
 #[cfg(test)]
 mod new_game {
+    use crate::bit_mask::BitMask;
     use crate::game::Game;
+    use crate::game_record::RecordError;
+    use crate::move_outcome::{MoveError, MoveOutcome};
     use crate::play_markers::PlayMarkers;
+    use crate::board::Board;
+    use crate::position_error::PositionError;
+    use crate::position_notation::NotationError;
 
     const CATS_GAME: [u8; 9] = [0, 4, 2, 1, 7, 5, 3, 6, 8];
 
     #[test]
     fn is_not_over() {
-        let game = Game::new();
-        assert!(!game.is_over)
+        let game = Game::new(3, 3);
+        assert!(!game.is_over())
     }
 
     #[test]
     fn has_empty_board() {
-        let game = Game::new();
-        let board = game.board;
-        assert!(board.spaces.is_empty())
+        let game = Game::new(3, 3);
+        let board = *game.get_board();
+        assert_eq!(board.get_played_count(), 0)
     }
 
     #[test]
     fn x_plays_first() {
-        let mut game = Game::new();
-        game.play(0);
-        assert_eq!(game.board.get_space_marker(&0), Some(&PlayMarkers::X));
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
+        assert_eq!(game.get_board().get_space_marker(&0), Some(&PlayMarkers::X));
     }
 
     #[test]
     fn o_plays_second() {
-        let mut game = Game::new();
-        game.play(0);
-        game.play(1);
-        assert_eq!(game.board.get_space_marker(&1), Some(&PlayMarkers::O));
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
+        game.play(1).unwrap();
+        assert_eq!(game.get_board().get_space_marker(&1), Some(&PlayMarkers::O));
     }
 
     #[test]
     fn board_unchanged_after_playing_in_taken_position() {
-        let mut game = Game::new();
-        game.play(0);
-        game.play(0);
-        assert_eq!(game.board.get_space_marker(&0), Some(&PlayMarkers::X))
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
+        assert_eq!(game.play(0), Err(MoveError::Occupied(0)));
+        assert_eq!(game.get_board().get_space_marker(&0), Some(&PlayMarkers::X))
     }
 
     #[test]
     fn cats_game_is_over() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for space in CATS_GAME {
-            game.play(space);
+            game.play(space).unwrap();
         }
-        assert!(game.is_over);
+        assert!(game.is_over());
     }
 
     #[test]
     fn cats_game_has_no_winner() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for space in CATS_GAME {
-            game.play(space);
+            game.play(space).unwrap();
         }
         assert_eq!(game.winner, None);
     }
@@ -168,9 +435,9 @@ mod new_game {
             [0, 1, 4, 2, 8], [2, 3, 4, 5, 6],
         ];
         for game_play in games_where_x_wins {
-            let mut game = Game::new();
+            let mut game = Game::new(3, 3);
             for space in game_play {
-                game.play(space);
+                game.play(space).unwrap();
             }
             assert_eq!(game.winner, Some(PlayMarkers::X));
         }
@@ -185,9 +452,9 @@ mod new_game {
             [3, 0, 1, 4, 2, 8], [1, 2, 3, 4, 5, 6],
         ];
         for game_play in games_where_o_wins {
-            let mut game = Game::new();
+            let mut game = Game::new(3, 3);
             for space in game_play {
-                game.play(space);
+                game.play(space).unwrap();
             }
             assert_eq!(game.winner, Some(PlayMarkers::O));
         }
@@ -195,20 +462,384 @@ mod new_game {
 
     #[test]
     fn is_over_after_win() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for space in [0, 3, 1, 4, 2] {
-            game.play(space);
+            game.play(space).unwrap();
         }
-        assert_eq!(game.is_over, true);
+        assert!(game.is_over());
     }
 
     #[test]
     fn game_stays_the_same_after_playing_game_over() {
-        let mut game = Game::new();
+        let mut game = Game::new(3, 3);
         for space in [0, 3, 1, 4, 2] {
-            game.play(space);
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.play(5), Err(MoveError::GameOver));
+        assert_eq!(game.get_board().get_space_marker(&5), None)
+    }
+
+    #[test]
+    fn larger_board_needs_full_line() {
+        let mut game = Game::new(4, 4);
+        for space in [0, 4, 1, 5, 2, 6] {
+            game.play(space).unwrap();
+        }
+        assert!(!game.is_over());
+        assert_eq!(game.play(3), Ok(MoveOutcome::Won(PlayMarkers::X)));
+        assert_eq!(game.winner, Some(PlayMarkers::X));
+    }
+
+    #[test]
+    fn larger_board_with_shorter_win_length() {
+        let mut game = Game::new(5, 4);
+        for space in [6, 0, 12, 1, 18, 2, 24] {
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.winner, Some(PlayMarkers::X));
+    }
+
+    #[test]
+    fn larger_board_is_not_full_after_nine_plays() {
+        let mut game = Game::new(4, 3);
+        for space in [0, 1, 2, 4, 3, 5, 6, 7, 8] {
+            game.play(space).unwrap();
         }
-        game.play(5);
-        assert_eq!(game.board.get_space_marker(&5), None)
+        assert!(!game.get_board().is_full());
+        assert_eq!(game.get_available_plays(), vec![9, 10, 11, 12, 13, 14, 15]);
+    }
+
+    #[test]
+    fn rejects_space_outside_larger_board() {
+        let game = Game::new(4, 3);
+        assert!(game.is_valid_move(&15));
+        assert!(!game.is_valid_move(&16));
+    }
+
+    #[test]
+    fn history_records_valid_moves_only() {
+        let mut game = Game::new(3, 3);
+        game.play(4).unwrap();
+        assert_eq!(game.play(4), Err(MoveError::Occupied(4)));
+        game.play(0).unwrap();
+        assert_eq!(game.play(9), Err(MoveError::OutOfRange(9)));
+        assert_eq!(game.history(), &[4, 0]);
+        assert_eq!(game.last_move(), Some(0));
+    }
+
+    #[test]
+    fn undo_clears_last_space() {
+        let mut game = Game::new(3, 3);
+        game.play(4).unwrap();
+        game.play(0).unwrap();
+        assert_eq!(game.undo(), Some(0));
+        assert_eq!(game.get_board().get_space_marker(&0), None);
+        assert_eq!(game.get_active_marker(), PlayMarkers::O);
+        assert_eq!(game.history(), &[4]);
+    }
+
+    #[test]
+    fn undo_on_new_game_does_nothing() {
+        let mut game = Game::new(3, 3);
+        assert_eq!(game.undo(), None);
+        assert_eq!(game.last_move(), None);
+    }
+
+    #[test]
+    fn undo_reopens_won_game() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 4, 2] {
+            game.play(space).unwrap();
+        }
+        game.undo();
+        assert!(!game.is_over());
+        assert_eq!(game.get_winner(), None);
+        assert!(game.is_valid_move(&2));
+    }
+
+    #[test]
+    fn redo_restores_win() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 4, 2] {
+            game.play(space).unwrap();
+        }
+        game.undo();
+        game.undo();
+        assert_eq!(game.redo(), Some(4));
+        assert_eq!(game.redo(), Some(2));
+        assert_eq!(game.redo(), None);
+        assert!(game.is_over());
+        assert_eq!(game.get_winner(), Some(PlayMarkers::X));
+    }
+
+    #[test]
+    fn play_discards_undone_moves() {
+        let mut game = Game::new(3, 3);
+        game.play(0).unwrap();
+        game.undo();
+        game.play(4).unwrap();
+        assert_eq!(game.redo(), None);
+        assert_eq!(game.history(), &[4]);
+    }
+
+    #[test]
+    fn play_reports_continue() {
+        let mut game = Game::new(3, 3);
+        assert_eq!(game.play(4), Ok(MoveOutcome::Continue));
+    }
+
+    #[test]
+    fn play_reports_draw() {
+        let mut game = Game::new(3, 3);
+        for space in &CATS_GAME[..8] {
+            game.play(*space).unwrap();
+        }
+        assert_eq!(game.play(8), Ok(MoveOutcome::Draw));
+    }
+
+    #[test]
+    fn winning_last_space_is_not_a_draw() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 1, 2, 3, 5, 4, 7, 6] {
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.play(8), Ok(MoveOutcome::Won(PlayMarkers::X)));
+    }
+
+    #[test]
+    fn rejected_move_does_not_end_game() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 4] {
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.play(1), Err(MoveError::Occupied(1)));
+        assert!(!game.is_over());
+        assert_eq!(game.get_winner(), None);
+    }
+
+    #[test]
+    fn record_round_trip_keeps_position() {
+        let mut game = Game::new(4, 3);
+        for space in [5, 0, 6] {
+            game.play(space).unwrap();
+        }
+        let loaded = Game::from_record(&game.to_record()).unwrap();
+        assert_eq!(loaded.history(), &[5, 0, 6]);
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.get_win_length(), 3);
+    }
+
+    #[test]
+    fn record_has_result() {
+        let mut game = Game::new(3, 3);
+        for space in CATS_GAME {
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.to_record().to_string(), "[Size \"3\"]\n[WinLength \"3\"]\n[Result \"draw\"]\n\n0 4 2 1 7 5 3 6 8\n");
+    }
+
+    #[test]
+    fn record_with_invalid_move_is_rejected() {
+        let record = "[Size \"3\"]\n[WinLength \"3\"]\n\n0 0".parse().unwrap();
+        assert_eq!(Game::from_record(&record).err(), Some(RecordError::InvalidMove(0, MoveError::Occupied(0))));
+    }
+
+    #[test]
+    fn record_with_wrong_result_is_rejected() {
+        let record = "[Size \"3\"]\n[WinLength \"3\"]\n[Result \"O\"]\n\n0 3 1 4 2".parse().unwrap();
+        assert_eq!(Game::from_record(&record).err(), Some(RecordError::ResultMismatch("O".to_string(), "X".to_string())));
+    }
+
+    #[test]
+    fn record_without_size_is_rejected() {
+        let record = "[WinLength \"3\"]\n\n0".parse().unwrap();
+        assert_eq!(Game::from_record(&record).err(), Some(RecordError::MissingTag("Size".to_string())));
+    }
+
+    #[test]
+    fn records_completed_line() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 4, 2] {
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.get_completed_lines(), &[BitMask::from_spaces(&[0, 1, 2])]);
+        assert!(game.is_in_completed_line(1));
+        assert!(!game.is_in_completed_line(3));
+    }
+
+    #[test]
+    fn records_both_lines_completed_by_one_move() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 5, 4, 7, 6, 8, 2] {
+            game.play(space).unwrap();
+        }
+        assert_eq!(game.get_completed_lines().len(), 2);
+        assert!(game.get_completed_lines().contains(&BitMask::from_spaces(&[0, 1, 2])));
+        assert!(game.get_completed_lines().contains(&BitMask::from_spaces(&[2, 4, 6])));
+    }
+
+    #[test]
+    fn undo_clears_completed_lines() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 4, 2] {
+            game.play(space).unwrap();
+        }
+        game.undo();
+        assert!(game.get_completed_lines().is_empty());
+        game.redo();
+        assert_eq!(game.get_completed_lines().len(), 1);
+    }
+
+    #[test]
+    fn sets_up_position_from_notation() {
+        let game: Game = "X_O/XO_/X__ o".parse().unwrap();
+        assert_eq!(game.get_winner(), Some(PlayMarkers::X));
+        assert!(game.is_over());
+        assert_eq!(game.get_completed_lines(), &[BitMask::from_spaces(&[0, 3, 6])]);
+        assert!(game.history().is_empty());
+        assert_eq!(game.to_string(), "X_O/XO_/X__ o");
+    }
+
+    #[test]
+    fn plays_on_from_notation() {
+        let mut game = Game::from_notation("XO__/____/____/____ x 3").unwrap();
+        assert_eq!(game.get_win_length(), 3);
+        game.play(5).unwrap();
+        assert_eq!(game.to_notation(), "XO__/_X__/____/____ o 3");
+        assert_eq!(game.history(), &[5]);
+    }
+
+    #[test]
+    fn full_board_from_notation_is_over() {
+        let game = Game::from_notation("XOX/XOO/OXX o").unwrap();
+        assert!(game.is_over());
+        assert_eq!(game.get_winner(), None);
+    }
+
+    #[test]
+    fn rejects_impossible_positions_in_notation() {
+        assert_eq!(Game::from_notation("XX_/___/___ o").err(), Some(NotationError::InvalidPosition(PositionError::MarkerCount(2, 0))));
+        assert_eq!(Game::from_notation("X__/___/___ x").err(), Some(NotationError::WrongSideToMove(PlayMarkers::O)));
+    }
+
+    #[test]
+    fn validates_marker_counts() {
+        let mut board = Board::new(3);
+        board.play(0, &PlayMarkers::O);
+        assert_eq!(Game::from_board(board, 3).err(), Some(PositionError::MarkerCount(0, 1)));
+        board.play(1, &PlayMarkers::X);
+        board.play(2, &PlayMarkers::X);
+        board.play(3, &PlayMarkers::X);
+        assert_eq!(Game::from_board(board, 3).err(), Some(PositionError::MarkerCount(3, 1)));
+        board.play(4, &PlayMarkers::O);
+        assert!(Game::from_board(board, 3).is_ok());
+    }
+
+    #[test]
+    fn rejects_win_length_outside_board() {
+        assert_eq!(Game::from_board(Board::new(3), 0).err(), Some(PositionError::InvalidWinLength(0, 3)));
+        assert_eq!(Game::from_board(Board::new(3), 4).err(), Some(PositionError::InvalidWinLength(4, 3)));
+        assert!(Game::from_board(Board::new(3), 3).is_ok());
+    }
+
+    #[test]
+    fn validates_winners() {
+        let cases = [
+            ("XXX/OOO/___ x", PositionError::BothWon),
+            ("XXX/OO_/O__ x", PositionError::WrongWinner(PlayMarkers::X)),
+            ("OOO/XX_/X_X o", PositionError::WrongWinner(PlayMarkers::O)),
+            ("XXXO/O__O/__O_/XXXO o 3", PositionError::PlayAfterWin(PlayMarkers::X)),
+        ];
+        for (notation, error) in cases {
+            assert_eq!(Game::from_notation(notation).err(), Some(NotationError::InvalidPosition(error)));
+        }
+    }
+
+    #[test]
+    fn validates_win_finished_by_one_move() {
+        let game = Game::from_notation("XXX/OXO/OOX o").unwrap();
+        assert!(game.validate().is_ok());
+        assert_eq!(game.get_completed_lines().len(), 2);
+        assert_eq!(game.get_winner(), Some(PlayMarkers::X));
+    }
+
+    #[test]
+    fn played_games_are_valid() {
+        let mut game = Game::new(3, 3);
+        for space in CATS_GAME {
+            assert!(game.validate().is_ok());
+            game.play(space).unwrap();
+        }
+        assert!(game.validate().is_ok());
+        let from_board = Game::from_board(*game.get_board(), 3).unwrap();
+        assert!(from_board.is_over());
+        assert_eq!(from_board.get_winner(), None);
+    }
+
+    #[test]
+    fn record_keeps_start_position() {
+        let mut game = Game::from_notation("X__/_O_/___ x").unwrap();
+        game.play(8).unwrap();
+        let record = game.to_record();
+        assert_eq!(record.to_string(), "[Size \"3\"]\n[WinLength \"3\"]\n[Position \"X__/_O_/___ x\"]\n[Result \"*\"]\n\n8\n");
+        let loaded = Game::from_record(&record).unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.history(), &[8]);
+    }
+
+    #[test]
+    fn record_with_mismatched_position_is_rejected() {
+        let record = "[Size \"4\"]\n[WinLength \"3\"]\n[Position \"X__/___/___ o\"]\n\n".parse().unwrap();
+        assert_eq!(Game::from_record(&record).err(), Some(RecordError::InvalidTag("Position".to_string(), "X__/___/___ o".to_string())));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod game_serde {
+    use crate::game::Game;
+
+    #[test]
+    fn round_trips_game_in_progress() {
+        let mut game = Game::new(4, 3);
+        for space in [5, 0, 6] {
+            game.play(space).unwrap();
+        }
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(json, r#"{"size":4,"win_length":3,"moves":[5,0,6],"winner":null,"is_over":false}"#);
+        let loaded: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.history(), game.history());
+    }
+
+    #[test]
+    fn round_trips_won_game() {
+        let mut game = Game::new(3, 3);
+        for space in [0, 3, 1, 4, 2] {
+            game.play(space).unwrap();
+        }
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(json, r#"{"size":3,"win_length":3,"moves":[0,3,1,4,2],"winner":"X","is_over":true}"#);
+        let loaded: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_winner(), game.get_winner());
+        assert!(loaded.is_over());
+        assert_eq!(loaded.get_completed_lines(), game.get_completed_lines());
+    }
+
+    #[test]
+    fn round_trips_game_from_position() {
+        let mut game = Game::from_notation("X__/_O_/___ x").unwrap();
+        game.play(8).unwrap();
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(json, r#"{"size":3,"win_length":3,"position":"X__/_O_/___ x","moves":[8],"winner":null,"is_over":false}"#);
+        let loaded: Game = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_board(), game.get_board());
+        assert_eq!(loaded.history(), &[8]);
+    }
+
+    #[test]
+    fn rejects_inconsistent_games() {
+        assert!(serde_json::from_str::<Game>(r#"{"size":3,"win_length":4,"moves":[],"winner":null,"is_over":false}"#).is_err());
+        assert!(serde_json::from_str::<Game>(r#"{"size":3,"win_length":3,"moves":[0,0],"winner":null,"is_over":false}"#).is_err());
+        assert!(serde_json::from_str::<Game>(r#"{"size":3,"win_length":3,"moves":[0],"winner":"O","is_over":true}"#).is_err());
     }
 }
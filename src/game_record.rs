@@ -0,0 +1,154 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use crate::move_outcome::MoveError;
+use crate::position_notation::NotationError;
+
+pub const SIZE_TAG: &str = "Size";
+pub const WIN_LENGTH_TAG: &str = "WinLength";
+// Set up position the moves start from, left out when the game starts on an empty board.
+pub const POSITION_TAG: &str = "Position";
+pub const RESULT_TAG: &str = "Result";
+
+// A finished or unfinished game written as tag lines followed by the moves, e.g.
+//
+// [Size "3"]
+// [WinLength "3"]
+// [Result "*"]
+//
+// 0 4 8
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct GameRecord {
+    tags: Vec<(String, String)>,
+    moves: Vec<u8>,
+}
+
+impl GameRecord {
+    pub fn new(moves: Vec<u8>) -> Self {
+        Self {
+            tags: Vec::new(),
+            moves,
+        }
+    }
+
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(tag, _)| tag == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, existing)) => *existing = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn get_number_tag(&self, name: &str, range: RangeInclusive<u8>) -> Result<u8, RecordError> {
+        let value = self.get_tag(name).ok_or_else(|| RecordError::MissingTag(name.to_string()))?;
+        match value.parse() {
+            Ok(number) if range.contains(&number) => Ok(number),
+            _ => Err(RecordError::InvalidTag(name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn get_moves(&self) -> &[u8] {
+        &self.moves
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.tags {
+            writeln!(f, "[{} \"{}\"]", name, value)?;
+        }
+        let moves: Vec<String> = self.moves.iter().map(|space| space.to_string()).collect();
+        writeln!(f)?;
+        writeln!(f, "{}", moves.join(" "))
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut record = GameRecord::default();
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(tag) = line.strip_prefix('[') {
+                let (name, value) = tag.strip_suffix(']')
+                    .and_then(|tag| tag.split_once(' '))
+                    .and_then(|(name, value)| Some((name, value.strip_prefix('"')?.strip_suffix('"')?)))
+                    .ok_or_else(|| RecordError::InvalidLine(line.to_string()))?;
+                record.set_tag(name, value);
+            } else {
+                for space in line.split_whitespace() {
+                    let space = space.parse().map_err(|_| RecordError::InvalidLine(line.to_string()))?;
+                    record.moves.push(space);
+                }
+            }
+        }
+        Ok(record)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RecordError {
+    InvalidLine(String),
+    MissingTag(String),
+    InvalidTag(String, String),
+    InvalidPosition(NotationError),
+    InvalidMove(u8, MoveError),
+    ResultMismatch(String, String),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::InvalidLine(line) => write!(f, "Can't read line \"{}\".", line),
+            RecordError::MissingTag(name) => write!(f, "Missing {} tag.", name),
+            RecordError::InvalidTag(name, value) => write!(f, "Invalid {} tag \"{}\".", name, value),
+            RecordError::InvalidPosition(error) => write!(f, "Invalid position: {}", error),
+            RecordError::InvalidMove(space, error) => write!(f, "Can't replay move {}: {}", space, error),
+            RecordError::ResultMismatch(recorded, replayed) => {
+                write!(f, "Recorded result {} doesn't match replayed result {}.", recorded, replayed)
+            }
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+#[cfg(test)]
+mod record_format {
+    use crate::game_record::{GameRecord, RecordError};
+
+    #[test]
+    fn prints_tags_then_moves() {
+        let mut record = GameRecord::new(vec![0, 4, 8]);
+        record.set_tag("Size", "3");
+        record.set_tag("Result", "*");
+        assert_eq!(record.to_string(), "[Size \"3\"]\n[Result \"*\"]\n\n0 4 8\n");
+    }
+
+    #[test]
+    fn parses_printed_record() {
+        let mut record = GameRecord::new(vec![4, 0, 8]);
+        record.set_tag("Mode", "ai-last");
+        record.set_tag("X", "human");
+        assert_eq!(record.to_string().parse::<GameRecord>(), Ok(record));
+    }
+
+    #[test]
+    fn set_tag_replaces_existing_value() {
+        let mut record = GameRecord::default();
+        record.set_tag("Result", "*");
+        record.set_tag("Result", "X");
+        assert_eq!(record.get_tag("Result"), Some("X"));
+        assert_eq!(record.to_string(), "[Result \"X\"]\n\n\n");
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert_eq!("[Size 3]".parse::<GameRecord>(), Err(RecordError::InvalidLine("[Size 3]".to_string())));
+        assert_eq!("0 four".parse::<GameRecord>(), Err(RecordError::InvalidLine("0 four".to_string())));
+    }
+}
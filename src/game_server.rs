@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use clap::ArgEnum;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde_json::{json, Value};
+use crate::board::MAX_SIZE;
+use crate::cli_game_manager::{CLIGameManager, GameMode};
+use crate::difficulty::Difficulty;
+use crate::game::Game;
+use crate::http::{HttpRequest, HttpResponse};
+use crate::move_outcome::{MoveError, MoveOutcome};
+use crate::player::Player;
+
+// How long a client has to send its whole request, and may leave a write waiting, before the connection
+// is dropped.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// REST API over games kept in memory until the server stops:
+//
+// GET  /games               every game
+// POST /games               new game, from optional "mode", "difficulty", "size" and "win_length" fields
+// GET  /games/{id}          one game
+// POST /games/{id}/moves    play {"space": n}; in ai modes the ai answers straight away
+// GET  /games/{id}/ai-move  the move the ai would play next, without playing it
+pub struct GameServer {
+    games: BTreeMap<u32, ServerGame>,
+    next_id: u32,
+    size: u8,
+    win_length: u8,
+    rng: StdRng,
+    timeout: Duration,
+}
+
+struct ServerGame {
+    game: Game,
+    mode: GameMode,
+    difficulty: Difficulty,
+    ai: Box<dyn Player>,
+}
+
+impl GameServer {
+    pub fn new(size: u8, win_length: u8, rng: StdRng) -> Self {
+        Self {
+            games: BTreeMap::new(),
+            next_id: 1,
+            size,
+            win_length,
+            rng,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // Handles connections one at a time, which keeps every game consistent without locking. The timeout
+    // stops a client that goes quiet, or sends its request a byte at a time, from holding up everyone else.
+    pub fn serve(&mut self, listener: TcpListener) {
+        for stream in listener.incoming().flatten() {
+            self.handle_connection(stream);
+        }
+    }
+
+    fn handle_connection(&mut self, stream: TcpStream) {
+        let deadline = Instant::now() + self.timeout;
+        if stream.set_read_timeout(Some(self.timeout)).is_err() || stream.set_write_timeout(Some(self.timeout)).is_err() {
+            return;
+        }
+        let response = match HttpRequest::read_before(BufReader::new(&stream), deadline) {
+            Ok(request) => self.handle(&request),
+            Err(error) if Instant::now() >= deadline => HttpResponse::error(408, &error),
+            Err(error) => HttpResponse::error(400, &error),
+        };
+        let _result = response.write_to(&stream);
+    }
+
+    pub fn handle(&mut self, request: &HttpRequest) -> HttpResponse {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        let result = match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["games"]) => Ok(self.list_games()),
+            ("POST", ["games"]) => self.create_game(&request.body),
+            ("GET", ["games", id]) => self.get_game(id).map(|(id, game)| HttpResponse::json(200, &game.to_json(id))),
+            ("POST", ["games", id, "moves"]) => self.play_move(id, &request.body),
+            ("GET", ["games", id, "ai-move"]) => self.get_ai_move(id),
+            (_, ["games"] | ["games", _] | ["games", _, "moves" | "ai-move"]) => Err(HttpResponse::error(405, "Method not allowed.")),
+            _ => Err(HttpResponse::error(404, &format!("No route for {}.", request.path))),
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    fn list_games(&self) -> HttpResponse {
+        let games: Vec<Value> = self.games.iter().map(|(id, game)| game.to_json(*id)).collect();
+        HttpResponse::json(200, &Value::Array(games))
+    }
+
+    fn create_game(&mut self, body: &str) -> Result<HttpResponse, HttpResponse> {
+        let fields = parse_body(body)?;
+        let mode = match fields.get("mode") {
+            Some(mode) => GameMode::from_str(mode.as_str().unwrap_or_default(), true).map_err(|error| HttpResponse::error(400, &error))?,
+            None => GameMode::AiLast,
+        };
+        let difficulty = match fields.get("difficulty") {
+            Some(difficulty) => Difficulty::from_str(difficulty.as_str().unwrap_or_default(), true).map_err(|error| HttpResponse::error(400, &error))?,
+            None => Difficulty::Hard,
+        };
+        let size = get_number_field(&fields, "size", self.size, 1..=MAX_SIZE)?;
+        let win_length = get_number_field(&fields, "win_length", self.win_length.min(size), 1..=size)?;
+        let mut game = ServerGame {
+            game: Game::new(size, win_length),
+            mode,
+            difficulty,
+            ai: difficulty.get_player(StdRng::seed_from_u64(self.rng.gen())),
+        };
+        game.answer_with_ai();
+        let id = self.next_id;
+        self.next_id += 1;
+        let response = HttpResponse::json(201, &game.to_json(id));
+        self.games.insert(id, game);
+        Ok(response)
+    }
+
+    fn play_move(&mut self, id: &str, body: &str) -> Result<HttpResponse, HttpResponse> {
+        let fields = parse_body(body)?;
+        let space = match fields.get("space").and_then(Value::as_u64) {
+            Some(space) => u8::try_from(space).map_err(|_| HttpResponse::error(400, &format!("{} is not valid.", space)))?,
+            None => return Err(HttpResponse::error(400, "Body needs a \"space\" number.")),
+        };
+        let (id, game) = self.get_game_mut(id)?;
+        if game.is_ai_turn() {
+            return Err(HttpResponse::error(409, "It's the ai's turn."));
+        }
+        match game.game.play(space) {
+            Ok(outcome) => {
+                if outcome == MoveOutcome::Continue {
+                    game.answer_with_ai();
+                }
+                Ok(HttpResponse::json(200, &game.to_json(id)))
+            }
+            Err(error @ MoveError::OutOfRange(_)) => Err(HttpResponse::error(400, &error.to_string())),
+            Err(error) => Err(HttpResponse::error(409, &error.to_string())),
+        }
+    }
+
+    fn get_ai_move(&mut self, id: &str) -> Result<HttpResponse, HttpResponse> {
+        let (_, game) = self.get_game_mut(id)?;
+        if game.game.is_over() {
+            return Err(HttpResponse::error(409, &MoveError::GameOver.to_string()));
+        }
+        let space = game.ai.choose_move(&game.game);
+        Ok(HttpResponse::json(200, &json!({ "space": space })))
+    }
+
+    fn get_game(&self, id: &str) -> Result<(u32, &ServerGame), HttpResponse> {
+        let id = parse_id(id)?;
+        self.games.get(&id).map(|game| (id, game)).ok_or_else(|| no_game(id))
+    }
+
+    fn get_game_mut(&mut self, id: &str) -> Result<(u32, &mut ServerGame), HttpResponse> {
+        let id = parse_id(id)?;
+        self.games.get_mut(&id).map(|game| (id, game)).ok_or_else(|| no_game(id))
+    }
+}
+
+impl ServerGame {
+    fn is_ai_turn(&self) -> bool {
+        !self.game.is_over() && self.mode.is_ai(&self.game.get_active_marker())
+    }
+
+    fn answer_with_ai(&mut self) {
+        if self.is_ai_turn() {
+            let space = self.ai.choose_move(&self.game);
+            self.game.play(space).expect("AI picked an unavailable space.");
+        }
+    }
+
+    fn to_json(&self, id: u32) -> Value {
+        let game = &self.game;
+        let board: Vec<Option<&str>> = (0..game.get_board().get_space_count())
+            .map(|space| game.get_board().get_space_marker(&space).map(CLIGameManager::get_display_marker))
+            .collect();
+        let winning_spaces: Vec<u8> = (0..game.get_board().get_space_count()).filter(|space| game.is_in_completed_line(*space)).collect();
+        let status = match (game.get_winner(), game.is_over()) {
+            (Some(_), _) => "won",
+            (None, true) => "draw",
+            (None, false) => "in_progress",
+        };
+        json!({
+            "id": id,
+            "mode": self.mode.to_possible_value().unwrap().get_name(),
+            "difficulty": self.difficulty.to_possible_value().unwrap().get_name(),
+            "size": game.get_board().get_size(),
+            "win_length": game.get_win_length(),
+            "board": board,
+            "moves": game.history(),
+            "next": if game.is_over() { None } else { Some(CLIGameManager::get_display_marker(&game.get_active_marker())) },
+            "winner": game.get_winner().as_ref().map(CLIGameManager::get_display_marker),
+            "winning_spaces": winning_spaces,
+            "status": status,
+        })
+    }
+}
+
+// An empty body is treated as an empty object.
+fn parse_body(body: &str) -> Result<serde_json::Map<String, Value>, HttpResponse> {
+    if body.trim().is_empty() {
+        return Ok(serde_json::Map::new());
+    }
+    match serde_json::from_str(body) {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(_) => Err(HttpResponse::error(400, "Body must be a JSON object.")),
+        Err(error) => Err(HttpResponse::error(400, &format!("Invalid JSON: {}.", error))),
+    }
+}
+
+fn get_number_field(fields: &serde_json::Map<String, Value>, name: &str, default: u8, range: std::ops::RangeInclusive<u8>) -> Result<u8, HttpResponse> {
+    match fields.get(name) {
+        None => Ok(default),
+        Some(value) => value.as_u64()
+            .and_then(|number| u8::try_from(number).ok())
+            .filter(|number| range.contains(number))
+            .ok_or_else(|| HttpResponse::error(400, &format!("\"{}\" must be a number from {} to {}.", name, range.start(), range.end()))),
+    }
+}
+
+fn parse_id(id: &str) -> Result<u32, HttpResponse> {
+    id.parse().map_err(|_| HttpResponse::error(404, &format!("No game {}.", id)))
+}
+
+fn no_game(id: u32) -> HttpResponse {
+    HttpResponse::error(404, &format!("No game {}.", id))
+}
+
+#[cfg(test)]
+mod game_api {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use serde_json::{json, Value};
+    use crate::game_server::GameServer;
+    use crate::http::{HttpRequest, HttpResponse};
+
+    fn server() -> GameServer {
+        GameServer::new(3, 3, StdRng::seed_from_u64(0))
+    }
+
+    fn send(server: &mut GameServer, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let request = HttpRequest { method: method.to_string(), path: path.to_string(), body: body.to_string() };
+        let HttpResponse { status, body } = server.handle(&request);
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[test]
+    fn creates_and_gets_game() {
+        let mut server = server();
+        let (status, created) = send(&mut server, "POST", "/games", r#"{"mode": "local", "size": 4}"#);
+        assert_eq!(status, 201);
+        assert_eq!(created["id"], 1);
+        assert_eq!(created["size"], 4);
+        assert_eq!(created["win_length"], 3);
+        assert_eq!(created["board"].as_array().unwrap().len(), 16);
+        assert_eq!(created["next"], "X");
+        assert_eq!(created["status"], "in_progress");
+        assert_eq!(send(&mut server, "GET", "/games/1", ""), (200, created));
+    }
+
+    #[test]
+    fn ai_answers_moves() {
+        let mut server = server();
+        send(&mut server, "POST", "/games", "");
+        let (status, game) = send(&mut server, "POST", "/games/1/moves", r#"{"space": 0}"#);
+        assert_eq!(status, 200);
+        assert_eq!(game["moves"], json!([0, 4]));
+        assert_eq!(game["board"][4], "O");
+        assert_eq!(game["next"], "X");
+    }
+
+    #[test]
+    fn ai_moves_first_in_ai_first_games() {
+        let mut server = server();
+        let (_, game) = send(&mut server, "POST", "/games", r#"{"mode": "ai-first", "difficulty": "easy"}"#);
+        assert_eq!(game["moves"].as_array().unwrap().len(), 1);
+        assert_eq!(game["difficulty"], "easy");
+    }
+
+    #[test]
+    fn reports_finished_game() {
+        let mut server = server();
+        send(&mut server, "POST", "/games", r#"{"mode": "local"}"#);
+        for space in [0, 3, 1, 4] {
+            send(&mut server, "POST", "/games/1/moves", &format!("{{\"space\": {}}}", space));
+        }
+        let (_, game) = send(&mut server, "POST", "/games/1/moves", r#"{"space": 2}"#);
+        assert_eq!(game["status"], "won");
+        assert_eq!(game["winner"], "X");
+        assert_eq!(game["winning_spaces"], json!([0, 1, 2]));
+        assert_eq!(game["next"], Value::Null);
+        assert_eq!(send(&mut server, "GET", "/games/1/ai-move", ""), (409, json!({ "error": "The game is already over." })));
+    }
+
+    #[test]
+    fn suggests_ai_move_without_playing_it() {
+        let mut server = server();
+        send(&mut server, "POST", "/games", r#"{"mode": "local"}"#);
+        for space in [0, 3, 1] {
+            send(&mut server, "POST", "/games/1/moves", &format!("{{\"space\": {}}}", space));
+        }
+        assert_eq!(send(&mut server, "GET", "/games/1/ai-move", ""), (200, json!({ "space": 2 })));
+        assert_eq!(send(&mut server, "GET", "/games/1", "").1["moves"], json!([0, 3, 1]));
+    }
+
+    #[test]
+    fn lists_games() {
+        let mut server = server();
+        send(&mut server, "POST", "/games", "");
+        send(&mut server, "POST", "/games", r#"{"mode": "local"}"#);
+        let (status, games) = send(&mut server, "GET", "/games", "");
+        assert_eq!(status, 200);
+        let ids: Vec<&Value> = games.as_array().unwrap().iter().map(|game| &game["id"]).collect();
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut server = server();
+        send(&mut server, "POST", "/games", r#"{"mode": "local"}"#);
+        send(&mut server, "POST", "/games/1/moves", r#"{"space": 4}"#);
+        assert_eq!(send(&mut server, "POST", "/games/1/moves", r#"{"space": 4}"#).0, 409);
+        assert_eq!(send(&mut server, "POST", "/games/1/moves", r#"{"space": 9}"#), (400, json!({ "error": "9 is not valid." })));
+        assert_eq!(send(&mut server, "POST", "/games/1/moves", "{}").0, 400);
+        assert_eq!(send(&mut server, "POST", "/games/1/moves", "[").0, 400);
+        assert_eq!(send(&mut server, "GET", "/games/2", ""), (404, json!({ "error": "No game 2." })));
+        assert_eq!(send(&mut server, "DELETE", "/games/1", "").0, 405);
+        assert_eq!(send(&mut server, "GET", "/players", "").0, 404);
+        assert_eq!(send(&mut server, "POST", "/games", r#"{"size": 16}"#).0, 400);
+        assert_eq!(send(&mut server, "POST", "/games", r#"{"mode": "online"}"#).0, 400);
+    }
+
+    #[test]
+    fn serves_over_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || server().serve(listener));
+        let mut stream = TcpStream::connect(address).unwrap();
+        let body = r#"{"mode": "local"}"#;
+        write!(stream, "POST /games HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 201 Created\r\n"));
+        assert_eq!(serde_json::from_str::<Value>(body).unwrap()["mode"], "local");
+    }
+
+    #[test]
+    fn idle_connection_does_not_block_others() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || server().with_timeout(Duration::from_millis(100)).serve(listener));
+        let _idle = TcpStream::connect(address).unwrap();
+        let started = Instant::now();
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        write!(stream, "GET /games HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn slow_client_does_not_block_others() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || server().with_timeout(Duration::from_millis(200)).serve(listener));
+        let mut slow = TcpStream::connect(address).unwrap();
+        write!(slow, "GET /games HTTP/1.1\r\n").unwrap();
+        // Each byte arrives well within the read timeout, so only the deadline for the whole request helps.
+        thread::spawn(move || {
+            for _ in 0..50 {
+                thread::sleep(Duration::from_millis(50));
+                if slow.write_all(b"X").is_err() {
+                    break;
+                }
+            }
+        });
+        thread::sleep(Duration::from_millis(20));
+        let started = Instant::now();
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        write!(stream, "GET /games HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+    }
+}
@@ -0,0 +1,191 @@
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+
+// Requests past these sizes are refused rather than read into memory.
+const MAX_BODY_LENGTH: usize = 64 * 1024;
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+const MAX_HEADER_COUNT: usize = 100;
+
+// Just enough HTTP/1.1 for the game server: one request per connection, bodies sized by Content-Length.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpRequest {
+    pub fn read_from<R>(reader: R) -> Result<Self, String> where R: BufRead {
+        Self::read_until_deadline(reader, None)
+    }
+
+    // Gives up once the deadline passes, however the client spreads the request out over time.
+    pub fn read_before<R>(reader: R, deadline: Instant) -> Result<Self, String> where R: BufRead {
+        Self::read_until_deadline(reader, Some(deadline))
+    }
+
+    fn read_until_deadline<R>(mut reader: R, deadline: Option<Instant>) -> Result<Self, String> where R: BufRead {
+        let mut request_line = String::new();
+        read_line(&mut reader, &mut request_line, deadline)?;
+        let mut parts = request_line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method.to_string(), target),
+            _ => return Err(format!("Can't read request line \"{}\".", request_line.trim())),
+        };
+        let path = target.split('?').next().unwrap_or_default().to_string();
+        let mut content_length = 0;
+        let mut header_count = 0;
+        loop {
+            let mut header = String::new();
+            if read_line(&mut reader, &mut header, deadline)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            header_count += 1;
+            if header_count > MAX_HEADER_COUNT {
+                return Err(format!("Requests can't have more than {} headers.", MAX_HEADER_COUNT));
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().map_err(|_| format!("Invalid Content-Length \"{}\".", value.trim()))?;
+                }
+            }
+        }
+        if content_length > MAX_BODY_LENGTH {
+            return Err(format!("Body can't be longer than {} bytes.", MAX_BODY_LENGTH));
+        }
+        let mut body = Vec::with_capacity(content_length);
+        while body.len() < content_length {
+            check_deadline(deadline)?;
+            let available = reader.fill_buf().map_err(|error| error.to_string())?;
+            if available.is_empty() {
+                return Err("Body is shorter than its Content-Length.".to_string());
+            }
+            let used = available.len().min(content_length - body.len());
+            body.extend_from_slice(&available[..used]);
+            reader.consume(used);
+        }
+        let body = String::from_utf8(body).map_err(|_| "Body isn't valid UTF-8.".to_string())?;
+        Ok(Self { method, path, body })
+    }
+}
+
+impl HttpResponse {
+    pub fn json(status: u16, value: &serde_json::Value) -> Self {
+        Self {
+            status,
+            body: value.to_string(),
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+
+    pub fn write_to<W>(&self, mut writer: W) -> io::Result<()> where W: Write {
+        write!(writer, "HTTP/1.1 {} {}\r\n", self.status, get_reason(self.status))?;
+        write!(writer, "Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        writer.write_all(self.body.as_bytes())?;
+        writer.flush()
+    }
+}
+
+// Reads whatever has arrived at a time, so the deadline is checked however slowly the line comes in.
+fn read_line<R>(reader: &mut R, line: &mut String, deadline: Option<Instant>) -> Result<usize, String> where R: BufRead {
+    let mut bytes = Vec::new();
+    loop {
+        check_deadline(deadline)?;
+        let available = reader.fill_buf().map_err(|error| error.to_string())?;
+        if available.is_empty() {
+            break;
+        }
+        let (used, is_done) = match available.iter().position(|byte| *byte == b'\n') {
+            Some(end) => (end + 1, true),
+            None => (available.len(), false),
+        };
+        bytes.extend_from_slice(&available[..used]);
+        reader.consume(used);
+        if bytes.len() > MAX_LINE_LENGTH {
+            return Err(format!("Lines can't be longer than {} bytes.", MAX_LINE_LENGTH));
+        }
+        if is_done {
+            break;
+        }
+    }
+    line.push_str(&String::from_utf8(bytes).map_err(|_| "Request isn't valid UTF-8.".to_string())?);
+    Ok(line.len())
+}
+
+fn check_deadline(deadline: Option<Instant>) -> Result<(), String> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err("The request took too long to arrive.".to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn get_reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod http_messages {
+    use std::time::{Duration, Instant};
+    use crate::http::{HttpRequest, HttpResponse};
+
+    #[test]
+    fn reads_request_with_body() {
+        let request = HttpRequest::read_from(&b"POST /games/1/moves?x=1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 11\r\n\r\n{\"space\":4}"[..]);
+        assert_eq!(request, Ok(HttpRequest {
+            method: "POST".to_string(),
+            path: "/games/1/moves".to_string(),
+            body: "{\"space\":4}".to_string(),
+        }));
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        assert!(HttpRequest::read_from(&b"\r\n"[..]).is_err());
+        assert!(HttpRequest::read_from(&b"GET / HTTP/1.1\r\nContent-Length: ten\r\n\r\n"[..]).is_err());
+        assert!(HttpRequest::read_from(&b"GET / HTTP/1.1\r\nContent-Length: 100000\r\n\r\n"[..]).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        let long_header = format!("GET / HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(10 * 1024));
+        assert_eq!(HttpRequest::read_from(long_header.as_bytes()), Err("Lines can't be longer than 8192 bytes.".to_string()));
+        let many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Padding: a\r\n".repeat(101));
+        assert_eq!(HttpRequest::read_from(many_headers.as_bytes()), Err("Requests can't have more than 100 headers.".to_string()));
+        let allowed = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Padding: a\r\n".repeat(100));
+        assert!(HttpRequest::read_from(allowed.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn gives_up_after_deadline() {
+        let request = HttpRequest::read_before(&b"GET /games HTTP/1.1\r\n\r\n"[..], Instant::now());
+        assert_eq!(request, Err("The request took too long to arrive.".to_string()));
+        let later = Instant::now() + Duration::from_secs(60);
+        assert!(HttpRequest::read_before(&b"GET /games HTTP/1.1\r\n\r\n"[..], later).is_ok());
+        assert!(HttpRequest::read_before(&b"POST /games HTTP/1.1\r\nContent-Length: 5\r\n\r\n{}"[..], later).is_err());
+    }
+
+    #[test]
+    fn writes_json_response() {
+        let mut output = Vec::new();
+        HttpResponse::error(404, "No game 7.").write_to(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 22\r\nConnection: close\r\n\r\n{\"error\":\"No game 7.\"}");
+    }
+}
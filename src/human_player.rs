@@ -0,0 +1,60 @@
+use std::io::{BufRead, Write};
+use crate::game::Game;
+use crate::player::Player;
+
+// Reads moves as space numbers, one per line, asking again until a valid one is entered.
+pub struct HumanPlayer<R, W> {
+    reader: R,
+    writer: W,
+}
+
+impl<R, W> HumanPlayer<R, W> where R: BufRead, W: Write {
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader,
+            writer,
+        }
+    }
+}
+
+impl<R, W> Player for HumanPlayer<R, W> where R: BufRead, W: Write {
+    fn choose_move(&mut self, game: &Game) -> u8 {
+        loop {
+            let _result = writeln!(self.writer, "{:?}'s turn! Enter number: ", game.get_active_marker());
+            let mut s = String::new();
+            let read = self.reader.read_line(&mut s).expect("Unable to read.");
+            assert!(read > 0, "No more input to read a move from.");
+            let error = match s.trim().parse::<u8>() {
+                Ok(space) => match game.check_move(&space) {
+                    Ok(()) => return space,
+                    Err(error) => error.to_string(),
+                },
+                Err(_) => format!("{} is not valid.", s.trim()),
+            };
+            let _result = writeln!(self.writer, "Error: {}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod human_input {
+    use crate::game::Game;
+    use crate::human_player::HumanPlayer;
+    use crate::player::Player;
+
+    #[test]
+    fn asks_again_after_invalid_input() {
+        let mut game = Game::new(3, 3);
+        game.play(4).unwrap();
+        let mut output = Vec::new();
+        let mut player = HumanPlayer::new(&b"a\n4\n9\n0\n"[..], &mut output);
+        assert_eq!(player.choose_move(&game), 0);
+        assert_eq!(String::from_utf8(output).unwrap(), "O's turn! Enter number: \n\
+            Error: a is not valid.\n\
+            O's turn! Enter number: \n\
+            Error: Can't play in position 4, as it has been already played.\n\
+            O's turn! Enter number: \n\
+            Error: 9 is not valid.\n\
+            O's turn! Enter number: \n");
+    }
+}
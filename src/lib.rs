@@ -0,0 +1,38 @@
+// Tic-tac-toe on boards up to 15 x 15: the game rules, ai engines and the front ends built on them.
+pub mod game;
+pub mod play_markers;
+pub mod board;
+pub mod symmetry;
+pub mod move_outcome;
+pub mod game_record;
+pub mod bit_mask;
+pub mod winning_plays;
+pub mod cli_game_manager;
+pub mod cli_command;
+pub mod ai;
+pub mod ai2;
+mod zobrist;
+pub mod difficulty;
+mod move_scores;
+pub mod scored_move;
+pub mod player;
+pub mod random_player;
+pub mod human_player;
+pub mod notation;
+pub mod position_notation;
+pub mod position_error;
+pub mod replay;
+pub mod scoreboard;
+pub mod tournament;
+pub mod engine_protocol;
+pub mod network_play;
+pub mod network_host;
+pub mod network_client;
+pub mod http;
+pub mod game_server;
+pub mod tui_game_manager;
+
+pub use board::Board;
+pub use game::Game;
+pub use move_outcome::{MoveError, MoveOutcome};
+pub use play_markers::PlayMarkers;
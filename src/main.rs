@@ -1,30 +1,219 @@
-use clap::Parser;
-use std::io;
-use crate::game::Game;
-use crate::cli_game_manager::{CLIGameManager, GameMode};
-use crate::play_markers::PlayMarkers;
+use clap::{ArgEnum, ErrorKind, IntoApp, Parser, Subcommand};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::{fs, io};
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use rust_tic_tac_toe::Game;
+use rust_tic_tac_toe::cli_game_manager::{CLIGameManager, GameMode, DIFFICULTY_TAG, MODE_TAG};
+use rust_tic_tac_toe::game_record::GameRecord;
+use rust_tic_tac_toe::game_server::GameServer;
+use rust_tic_tac_toe::notation::MoveNotation;
+use rust_tic_tac_toe::difficulty::Difficulty;
+use rust_tic_tac_toe::engine_protocol::EngineProtocol;
+use rust_tic_tac_toe::network_client::NetworkClient;
+use rust_tic_tac_toe::network_host::NetworkHost;
+use rust_tic_tac_toe::network_play::DEFAULT_PORT;
+use rust_tic_tac_toe::replay::ReplayViewer;
+use rust_tic_tac_toe::{board, tournament};
+use rust_tic_tac_toe::tournament::Engine;
+use rust_tic_tac_toe::tui_game_manager::TuiGameManager;
 
-mod game;
-mod play_markers;
-mod board;
-mod winning_plays;
-mod cli_game_manager;
-mod ai;
-mod ai2;
 
 
 #[derive(Parser, PartialEq)]
 struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
     // Whether to play against "ai-first", "ai-last" or "local"
     #[clap(arg_enum, default_value_t = GameMode::AiLast)]
     mode: GameMode,
+    // Number of rows and columns on the board
+    #[clap(long, global = true, default_value_t = 3)]
+    size: u8,
+    // Number of markers in a row needed to win
+    #[clap(long, global = true, default_value_t = 3)]
+    win_length: u8,
+    // How well the ai plays: "easy", "medium" or "hard"
+    #[clap(long, arg_enum, default_value_t = Difficulty::Hard)]
+    difficulty: Difficulty,
+    // Seed for the ai's random choices, to replay a session exactly
+    #[clap(long, global = true)]
+    seed: Option<u64>,
+    // Saved game file to resume, written with ":save <file>" during a game
+    #[clap(long)]
+    load: Option<String>,
+    // Position to start from instead of an empty board, like "XO_/_X_/___ o"
+    #[clap(long, conflicts_with = "load")]
+    position: Option<String>,
+    // How moves are entered: "index" (0-8), "coordinate" (b2), "row-column" (2,2) or "numpad" (3 x 3 only)
+    #[clap(long, arg_enum, default_value_t = MoveNotation::Index)]
+    notation: MoveNotation,
+    // Interface to play in: "cli" for typed moves or "tui" for a full screen board with a cursor
+    #[clap(long, arg_enum, default_value_t = Ui::Cli)]
+    ui: Ui,
+    // Play a match of up to this many games, swapping who moves first each game
+    #[clap(long)]
+    best_of: Option<u32>,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, PartialEq)]
+enum Ui {
+    Cli,
+    Tui,
+}
+
+#[derive(Subcommand, PartialEq)]
+enum Command {
+    // Step through a saved game move by move
+    Replay {
+        file: String,
+    },
+    // Play two engines against each other, alternating who starts
+    Tournament {
+        #[clap(arg_enum)]
+        first: Engine,
+        #[clap(arg_enum)]
+        second: Engine,
+        #[clap(long, default_value_t = 100)]
+        games: u32,
+    },
+    // Let another program drive the ai through a line based protocol on stdin and stdout
+    Engine,
+    // Wait for an opponent to join over the network, playing X
+    Host {
+        #[clap(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+    },
+    // Join a hosted game at host:port, playing O
+    Join {
+        address: String,
+    },
+    // Serve a JSON API for creating and playing games over HTTP
+    Serve {
+        #[clap(long, default_value_t = 8080)]
+        port: u16,
+    },
 }
 
 fn main() {
     let args = Cli::parse();
-    let mut cli_game = CLIGameManager::new(args.mode);
-    let stdio = io::stdin();
-    let reader = stdio.lock();
-    let writer = io::stdout();
-    cli_game.start(reader, writer);
+    if !(1..=board::MAX_SIZE).contains(&args.size) {
+        let message = format!("size must be between 1 and {}", board::MAX_SIZE);
+        Cli::into_app().error(ErrorKind::ValueValidation, message).exit();
+    }
+    if !(1..=args.size).contains(&args.win_length) {
+        let message = "win-length must be between 1 and the board size";
+        Cli::into_app().error(ErrorKind::ValueValidation, message).exit();
+    }
+    if args.best_of == Some(0) {
+        Cli::into_app().error(ErrorKind::InvalidValue, "best-of must be at least 1").exit();
+    }
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    match &args.command {
+        Some(Command::Replay { file }) => {
+            let game = read_record(file)
+                .and_then(|record| Game::from_record(&record).map_err(|error| format!("{}: {}", file, error)))
+                .unwrap_or_else(|message| Cli::into_app().error(ErrorKind::Io, message).exit());
+            let stdio = io::stdin();
+            ReplayViewer::new(game).start(stdio.lock(), io::stdout());
+        }
+        Some(Command::Tournament { first, second, games }) => {
+            if let Some(engine) = [first, second].into_iter().find(|engine| !engine.supports_size(args.size)) {
+                let message = format!("the {} engine only plays on boards up to 3 x 3", engine.get_name());
+                Cli::into_app().error(ErrorKind::ValueValidation, message).exit();
+            }
+            let stats = tournament::run(*first, *second, *games, args.size, args.win_length, &mut rng);
+            print!("{}", stats.report(*first, *second));
+        }
+        Some(Command::Engine) => {
+            let stdio = io::stdin();
+            EngineProtocol::new(args.size, args.win_length, rng).start(stdio.lock(), io::stdout());
+        }
+        Some(Command::Host { port }) => {
+            let listener = TcpListener::bind(("0.0.0.0", *port))
+                .unwrap_or_else(|error| Cli::into_app().error(ErrorKind::Io, format!("can't listen on port {}: {}", port, error)).exit());
+            println!("Waiting for an opponent on port {}...", port);
+            let (stream, address) = listener.accept()
+                .unwrap_or_else(|error| Cli::into_app().error(ErrorKind::Io, error).exit());
+            println!("{} joined.", address);
+            let game = Game::new(args.size, args.win_length);
+            let remote_reader = BufReader::new(stream.try_clone().expect("Unable to clone connection."));
+            let stdio = io::stdin();
+            NetworkHost::new(game, remote_reader, stream).start(stdio.lock(), io::stdout());
+        }
+        Some(Command::Join { address }) => {
+            let stream = TcpStream::connect(address)
+                .unwrap_or_else(|error| Cli::into_app().error(ErrorKind::Io, format!("can't connect to {}: {}", address, error)).exit());
+            let remote_reader = BufReader::new(stream.try_clone().expect("Unable to clone connection."));
+            let stdio = io::stdin();
+            NetworkClient::new(remote_reader, stream).start(stdio.lock(), io::stdout());
+        }
+        Some(Command::Serve { port }) => {
+            let listener = TcpListener::bind(("0.0.0.0", *port))
+                .unwrap_or_else(|error| Cli::into_app().error(ErrorKind::Io, format!("can't listen on port {}: {}", port, error)).exit());
+            println!("Serving games on port {}...", port);
+            GameServer::new(args.size, args.win_length, rng).serve(listener);
+        }
+        None => {
+            let (mode, game, difficulty) = match &args.load {
+                Some(path) => load_game(path, &args).unwrap_or_else(|message| {
+                    Cli::into_app().error(ErrorKind::Io, message).exit()
+                }),
+                None => {
+                    let game = match &args.position {
+                        Some(position) => Game::from_notation(position).unwrap_or_else(|error| {
+                            Cli::into_app().error(ErrorKind::InvalidValue, error).exit()
+                        }),
+                        None => Game::new(args.size, args.win_length),
+                    };
+                    (args.mode.clone(), game, args.difficulty)
+                }
+            };
+            if args.ui == Ui::Tui {
+                // The board is played with the cursor, one game at a time.
+                if args.best_of.is_some() || args.notation != MoveNotation::Index {
+                    Cli::into_app().error(ErrorKind::ArgumentConflict, "--best-of and --notation can't be used with --ui tui").exit();
+                }
+                if let Err(error) = TuiGameManager::new(mode, game, difficulty, rng).start(io::stdout()) {
+                    Cli::into_app().error(ErrorKind::Io, error).exit();
+                }
+                return;
+            }
+            if !args.notation.supports_size(game.get_board().get_size()) {
+                Cli::into_app().error(ErrorKind::ArgumentConflict, "numpad notation needs a 3 x 3 board").exit();
+            }
+            let mut cli_game = CLIGameManager::new(mode, game, difficulty, rng).with_notation(args.notation);
+            let stdio = io::stdin();
+            let reader = stdio.lock();
+            let writer = io::stdout();
+            match args.best_of {
+                Some(best_of) => cli_game.start_match(best_of, reader, writer),
+                None => cli_game.start(reader, writer),
+            }
+        }
+    }
+}
+
+// Mode and difficulty come from the saved game, falling back to the command line.
+fn load_game(path: &str, args: &Cli) -> Result<(GameMode, Game, Difficulty), String> {
+    let record = read_record(path)?;
+    let game = Game::from_record(&record).map_err(|error| format!("{}: {}", path, error))?;
+    let mode = match record.get_tag(MODE_TAG) {
+        Some(mode) => GameMode::from_str(mode, true)?,
+        None => args.mode.clone(),
+    };
+    let difficulty = match record.get_tag(DIFFICULTY_TAG) {
+        Some(difficulty) => Difficulty::from_str(difficulty, true)?,
+        None => args.difficulty,
+    };
+    Ok((mode, game, difficulty))
+}
+
+fn read_record(path: &str) -> Result<GameRecord, String> {
+    let contents = fs::read_to_string(path).map_err(|error| format!("can't read {}: {}", path, error))?;
+    contents.parse().map_err(|error| format!("{}: {}", path, error))
 }
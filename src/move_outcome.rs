@@ -0,0 +1,29 @@
+use std::error::Error;
+use std::fmt;
+use crate::play_markers::PlayMarkers;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MoveOutcome {
+    Won(PlayMarkers),
+    Draw,
+    Continue,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MoveError {
+    OutOfRange(u8),
+    Occupied(u8),
+    GameOver,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::OutOfRange(space) => write!(f, "{} is not valid.", space),
+            MoveError::Occupied(space) => write!(f, "Can't play in position {}, as it has been already played.", space),
+            MoveError::GameOver => write!(f, "The game is already over."),
+        }
+    }
+}
+
+impl Error for MoveError {}
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+// Ties go to the smallest key, so the result does not depend on the map's iteration order.
+pub fn get_max_key<K, V>(a_hash_map: &HashMap<K, V>) -> Option<&K> where K: Ord, V: Ord,
+{
+    a_hash_map
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(k, _v)| k)
+}
+
+#[cfg(test)]
+mod max_key {
+    use std::collections::HashMap;
+    use crate::move_scores::get_max_key;
+
+    #[test]
+    fn picks_smallest_key_on_tie() {
+        let scores = HashMap::from([(5u8, 1), (2u8, 3), (7u8, 3), (4u8, 3)]);
+        assert_eq!(get_max_key(&scores), Some(&2));
+    }
+}
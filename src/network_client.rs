@@ -0,0 +1,105 @@
+use std::io::{BufRead, Write};
+use crate::board::MAX_SIZE;
+use crate::game::Game;
+use crate::network_play::{format_view, NetworkMessage, CLIENT_MARKER};
+
+// The joining side of a network game, which rebuilds the host's game from every state it is sent
+// and only asks the host to play its moves.
+pub struct NetworkClient<R, W> {
+    game: Option<Game>,
+    remote_reader: R,
+    remote_writer: W,
+    error: Option<String>,
+}
+
+impl<R, W> NetworkClient<R, W> where R: BufRead, W: Write {
+    pub fn new(remote_reader: R, remote_writer: W) -> Self {
+        Self {
+            game: None,
+            remote_reader,
+            remote_writer,
+            error: None,
+        }
+    }
+
+    pub fn start<L, O>(&mut self, mut local_reader: L, mut writer: O) where L: BufRead, O: Write {
+        loop {
+            if let Err(message) = self.receive() {
+                let _result = writeln!(writer, "{}", message);
+                break;
+            }
+            let game = match &self.game {
+                Some(game) => game,
+                None => continue,
+            };
+            let _result = writeln!(writer, "{}", format_view(game, CLIENT_MARKER, &self.error));
+            if game.is_over() {
+                break;
+            }
+            if game.get_active_marker() == CLIENT_MARKER && !self.input_move(&mut local_reader, &mut writer) {
+                let _result = writeln!(writer, "Game ended early.");
+                break;
+            }
+        }
+    }
+
+    // Reads one message from the host, failing once the game can't go on.
+    fn receive(&mut self) -> Result<(), String> {
+        let mut s = String::new();
+        match self.remote_reader.read_line(&mut s) {
+            Ok(0) | Err(_) => return Err("Game ended early.".to_string()),
+            Ok(_) => {}
+        }
+        match s.parse() {
+            Ok(NetworkMessage::State { size, win_length, moves }) => {
+                self.game = Some(replay(size, win_length, &moves).ok_or("The host sent an invalid game.")?);
+                self.error = None;
+            }
+            Ok(NetworkMessage::Error(message)) => self.error = Some(format!("Error: {}\n", message)),
+            Ok(NetworkMessage::Quit) => return Err("Game ended early.".to_string()),
+            Ok(NetworkMessage::Move(_)) | Err(_) => return Err("The host sent an unexpected message.".to_string()),
+        }
+        Ok(())
+    }
+
+    // Sends the next move typed in, asking again until one is a number. Returns false once the player quits.
+    fn input_move<L, O>(&mut self, mut local_reader: L, mut writer: O) -> bool where L: BufRead, O: Write {
+        loop {
+            let mut s = String::new();
+            let read = local_reader.read_line(&mut s).expect("Unable to read.");
+            let input = s.trim();
+            if read == 0 || input == ":quit" {
+                self.send(&NetworkMessage::Quit);
+                return false;
+            }
+            match input.parse::<u8>() {
+                Ok(space) => {
+                    self.send(&NetworkMessage::Move(space));
+                    return true;
+                }
+                Err(_) => {
+                    self.error = Some(format!("Error: {} is not valid.\n", input));
+                    if let Some(game) = &self.game {
+                        let _result = writeln!(writer, "{}", format_view(game, CLIENT_MARKER, &self.error));
+                    }
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, message: &NetworkMessage) {
+        let _result = writeln!(self.remote_writer, "{}", message);
+        let _result = self.remote_writer.flush();
+    }
+}
+
+fn replay(size: u8, win_length: u8, moves: &[u8]) -> Option<Game> {
+    if !(1..=MAX_SIZE).contains(&size) || !(1..=size).contains(&win_length) {
+        return None;
+    }
+    let mut game = Game::new(size, win_length);
+    for space in moves {
+        game.play(*space).ok()?;
+    }
+    Some(game)
+}
@@ -0,0 +1,138 @@
+use std::io::{BufRead, Write};
+use crate::game::Game;
+use crate::network_play::{format_view, NetworkMessage, HOST_MARKER};
+
+// The side of a network game that owns the Game, taking its own moves from the terminal and the
+// opponent's from the connection.
+pub struct NetworkHost<R, W> {
+    game: Game,
+    remote_reader: R,
+    remote_writer: W,
+    error: Option<String>,
+}
+
+impl<R, W> NetworkHost<R, W> where R: BufRead, W: Write {
+    pub fn new(game: Game, remote_reader: R, remote_writer: W) -> Self {
+        Self {
+            game,
+            remote_reader,
+            remote_writer,
+            error: None,
+        }
+    }
+
+    pub fn start<L, O>(&mut self, mut local_reader: L, mut writer: O) where L: BufRead, O: Write {
+        self.send(&NetworkMessage::from_game(&self.game));
+        loop {
+            let _result = writeln!(writer, "{}", format_view(&self.game, HOST_MARKER, &self.error));
+            if self.game.is_over() {
+                break;
+            }
+            let is_connected = if self.game.get_active_marker() == HOST_MARKER {
+                self.local_turn(&mut local_reader)
+            } else {
+                self.remote_turn()
+            };
+            if !is_connected {
+                let _result = writeln!(writer, "Game ended early.");
+                break;
+            }
+        }
+    }
+
+    // Returns false once the host quits.
+    fn local_turn<L>(&mut self, mut local_reader: L) -> bool where L: BufRead {
+        let mut s = String::new();
+        let read = local_reader.read_line(&mut s).expect("Unable to read.");
+        let input = s.trim();
+        if read == 0 || input == ":quit" {
+            self.send(&NetworkMessage::Quit);
+            return false;
+        }
+        match input.parse::<u8>() {
+            Ok(space) => match self.game.play(space) {
+                Ok(_) => {
+                    self.error = None;
+                    self.send(&NetworkMessage::from_game(&self.game));
+                }
+                Err(error) => self.error = Some(format!("Error: {}\n", error)),
+            },
+            Err(_) => self.error = Some(format!("Error: {} is not valid.\n", input)),
+        }
+        true
+    }
+
+    // Returns false once the opponent quits or disconnects.
+    fn remote_turn(&mut self) -> bool {
+        let mut s = String::new();
+        match self.remote_reader.read_line(&mut s) {
+            Ok(0) | Err(_) => return false,
+            Ok(_) => {}
+        }
+        match s.parse() {
+            Ok(NetworkMessage::Move(space)) => match self.game.play(space) {
+                Ok(_) => self.send(&NetworkMessage::from_game(&self.game)),
+                Err(error) => self.send(&NetworkMessage::Error(error.to_string())),
+            },
+            Ok(NetworkMessage::Quit) => return false,
+            Ok(_) => self.send(&NetworkMessage::Error("Only moves can be sent to the host.".to_string())),
+            Err(error) => self.send(&NetworkMessage::Error(error)),
+        }
+        true
+    }
+
+    fn send(&mut self, message: &NetworkMessage) {
+        let _result = writeln!(self.remote_writer, "{}", message);
+        let _result = self.remote_writer.flush();
+    }
+}
+
+#[cfg(test)]
+mod network_game {
+    use std::io::BufReader;
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use crate::game::Game;
+    use crate::network_client::NetworkClient;
+    use crate::network_host::NetworkHost;
+
+    // Plays a whole game over a localhost connection, returning what the host and client printed.
+    fn play_over_tcp(host_input: &'static [u8], client_input: &'static [u8]) -> (String, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let stream = TcpStream::connect(address).unwrap();
+            let mut output = Vec::new();
+            NetworkClient::new(BufReader::new(stream.try_clone().unwrap()), stream).start(client_input, &mut output);
+            String::from_utf8(output).unwrap()
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let mut output = Vec::new();
+        NetworkHost::new(Game::new(3, 3), BufReader::new(stream.try_clone().unwrap()), stream).start(host_input, &mut output);
+        (String::from_utf8(output).unwrap(), client.join().unwrap())
+    }
+
+    #[test]
+    fn plays_game_between_host_and_client() {
+        let (host, client) = play_over_tcp(b"0\n1\n2\n", b"3\n4\n");
+        assert!(host.starts_with("_|_|_\n_|_|_\n_|_|_\nYou play X. Your turn!\n"));
+        assert!(host.ends_with("X|X|X\no|o|_\n_|_|_\nX Wins!\n\n"));
+        assert!(client.starts_with("_|_|_\n_|_|_\n_|_|_\nYou play O. Waiting for X...\n"));
+        assert!(client.ends_with("X|X|X\no|o|_\n_|_|_\nX Wins!\n\n"));
+    }
+
+    #[test]
+    fn host_rejects_client_moves() {
+        let (host, client) = play_over_tcp(b"0\n1\n2\n", b"0\n9\n3\n4\n");
+        assert!(client.contains("Error: Can't play in position 0, as it has been already played.\n"));
+        assert!(client.contains("Error: 9 is not valid.\n"));
+        assert!(host.ends_with("X Wins!\n\n"));
+    }
+
+    #[test]
+    fn ends_when_a_player_quits() {
+        let (host, client) = play_over_tcp(b"4\n", b":quit\n");
+        assert!(host.ends_with("Game ended early.\n"));
+        assert!(client.ends_with("Game ended early.\n"));
+    }
+}
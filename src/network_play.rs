@@ -0,0 +1,134 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::cli_game_manager::CLIGameManager;
+use crate::game::Game;
+use crate::play_markers::PlayMarkers;
+
+pub const DEFAULT_PORT: u16 = 7878;
+// The host always plays X and moves first.
+pub const HOST_MARKER: PlayMarkers = PlayMarkers::X;
+pub const CLIENT_MARKER: PlayMarkers = PlayMarkers::O;
+
+// One line sent over the connection. The host sends the whole game after every change and the client
+// only ever asks for moves, so the host's Game is the only one that decides what is legal.
+#[derive(Debug, PartialEq, Clone)]
+pub enum NetworkMessage {
+    State { size: u8, win_length: u8, moves: Vec<u8> },
+    Error(String),
+    Move(u8),
+    Quit,
+}
+
+impl NetworkMessage {
+    pub fn from_game(game: &Game) -> Self {
+        NetworkMessage::State {
+            size: game.get_board().get_size(),
+            win_length: game.get_win_length(),
+            moves: game.history().to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for NetworkMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkMessage::State { size, win_length, moves } => {
+                write!(f, "state {} {}", size, win_length)?;
+                for space in moves {
+                    write!(f, " {}", space)?;
+                }
+                Ok(())
+            }
+            NetworkMessage::Error(message) => write!(f, "error {}", message),
+            NetworkMessage::Move(space) => write!(f, "move {}", space),
+            NetworkMessage::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for NetworkMessage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, rest) = s.split_once(' ').unwrap_or((s, ""));
+        let invalid = || format!("Can't read message \"{}\".", s);
+        match name {
+            "state" => {
+                let numbers: Vec<u8> = rest.split_whitespace().map(str::parse).collect::<Result<_, _>>().map_err(|_| invalid())?;
+                match numbers.split_first() {
+                    Some((size, [win_length, moves @ ..])) => Ok(NetworkMessage::State {
+                        size: *size,
+                        win_length: *win_length,
+                        moves: moves.to_vec(),
+                    }),
+                    _ => Err(invalid()),
+                }
+            }
+            "error" => Ok(NetworkMessage::Error(rest.to_string())),
+            "move" => rest.parse().map(NetworkMessage::Move).map_err(|_| invalid()),
+            "quit" if rest.is_empty() => Ok(NetworkMessage::Quit),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// Board and status as seen by the player with the given marker.
+pub fn format_view(game: &Game, own_marker: PlayMarkers, error: &Option<String>) -> String {
+    let mut view = CLIGameManager::format_board_display(game);
+    if let Some(winner) = game.get_winner() {
+        view += format!("{} Wins!\n", CLIGameManager::get_display_marker(&winner)).as_str();
+        return view;
+    }
+    if game.is_over() {
+        return view + "Game Over!\n";
+    }
+    let active_marker = game.get_active_marker();
+    view += format!("You play {}. ", CLIGameManager::get_display_marker(&own_marker)).as_str();
+    if active_marker == own_marker {
+        let plays: Vec<String> = game.get_available_plays().iter().map(|space| space.to_string()).collect();
+        view += format!("Your turn!\nAvailable spaces in order from left to right and top to bottom: {}.\n", plays.join(", ")).as_str();
+        if let Some(error) = error {
+            view += error.as_str();
+        }
+        view += "Enter number: \n";
+    } else {
+        view += format!("Waiting for {}...\n", CLIGameManager::get_display_marker(&active_marker)).as_str();
+    }
+    view
+}
+
+#[cfg(test)]
+mod network_messages {
+    use crate::game::Game;
+    use crate::network_play::{format_view, NetworkMessage, CLIENT_MARKER, HOST_MARKER};
+
+    #[test]
+    fn messages_round_trip() {
+        for message in [
+            NetworkMessage::State { size: 3, win_length: 3, moves: vec![0, 4] },
+            NetworkMessage::State { size: 4, win_length: 3, moves: vec![] },
+            NetworkMessage::Error("The game is already over.".to_string()),
+            NetworkMessage::Move(8),
+            NetworkMessage::Quit,
+        ] {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_messages() {
+        assert_eq!("state 3".parse::<NetworkMessage>(), Err("Can't read message \"state 3\".".to_string()));
+        assert!("move x".parse::<NetworkMessage>().is_err());
+        assert!("play 4".parse::<NetworkMessage>().is_err());
+    }
+
+    #[test]
+    fn shows_whose_turn_it_is() {
+        let mut game = Game::new(3, 3);
+        game.play(4).unwrap();
+        assert_eq!(format_view(&game, HOST_MARKER, &None), "_|_|_\n_|X|_\n_|_|_\nYou play X. Waiting for O...\n");
+        let error = Some("Error: 9 is not valid.\n".to_string());
+        assert!(format_view(&game, CLIENT_MARKER, &error).ends_with("You play O. Your turn!\nAvailable spaces in order from left to right and top to bottom: 0, 1, 2, 3, 5, 6, 7, 8.\nError: 9 is not valid.\nEnter number: \n"));
+    }
+}
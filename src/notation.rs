@@ -0,0 +1,125 @@
+// Ways of naming a space. Coordinates and row,column pairs count rows from the top, starting at 1.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum MoveNotation {
+    // Space number 0 to size * size - 1, left to right and top to bottom
+    Index,
+    // Column letter then row number, like b2
+    Coordinate,
+    // Row and column numbers, like 2,2
+    RowColumn,
+    // Keys of a numeric keypad, 7 8 9 on the top row (3 x 3 boards only)
+    Numpad,
+}
+
+const NUMPAD_KEYS: [u8; 9] = [7, 8, 9, 4, 5, 6, 1, 2, 3];
+
+impl MoveNotation {
+    pub fn supports_size(&self, size: u8) -> bool {
+        *self != MoveNotation::Numpad || size == 3
+    }
+
+    // Index notation accepts any number so out of range spaces can be reported by the game.
+    pub fn parse(&self, input: &str, size: u8) -> Option<u8> {
+        let input = input.trim();
+        match self {
+            MoveNotation::Index => input.parse().ok(),
+            MoveNotation::Coordinate => {
+                let mut chars = input.chars();
+                let column = chars.next()?.to_ascii_lowercase();
+                if !column.is_ascii_lowercase() {
+                    return None;
+                }
+                let row = chars.as_str().parse::<u8>().ok()?;
+                get_space(row.checked_sub(1)?, column as u8 - b'a', size)
+            }
+            MoveNotation::RowColumn => {
+                let (row, column) = input.split_once(',')?;
+                let row = row.trim().parse::<u8>().ok()?.checked_sub(1)?;
+                let column = column.trim().parse::<u8>().ok()?.checked_sub(1)?;
+                get_space(row, column, size)
+            }
+            MoveNotation::Numpad => {
+                let key = input.parse::<u8>().ok()?;
+                NUMPAD_KEYS.iter().position(|k| *k == key).map(|space| space as u8)
+            }
+        }
+    }
+
+    pub fn format(&self, space: u8, size: u8) -> String {
+        let (row, column) = (space / size, space % size);
+        match self {
+            MoveNotation::Index => space.to_string(),
+            MoveNotation::Coordinate => format!("{}{}", (b'a' + column) as char, row + 1),
+            MoveNotation::RowColumn => format!("{},{}", row + 1, column + 1),
+            MoveNotation::Numpad => NUMPAD_KEYS[space as usize].to_string(),
+        }
+    }
+
+    pub fn get_prompt(&self) -> &'static str {
+        match self {
+            MoveNotation::Index | MoveNotation::Numpad => "Enter number: \n",
+            MoveNotation::Coordinate => "Enter coordinate: \n",
+            MoveNotation::RowColumn => "Enter row,column: \n",
+        }
+    }
+}
+
+fn get_space(row: u8, column: u8, size: u8) -> Option<u8> {
+    if row < size && column < size { Some(row * size + column) } else { None }
+}
+
+#[cfg(test)]
+mod move_notation {
+    use crate::notation::MoveNotation;
+
+    #[test]
+    fn parses_coordinates() {
+        assert_eq!(MoveNotation::Coordinate.parse("a1", 3), Some(0));
+        assert_eq!(MoveNotation::Coordinate.parse("B2", 3), Some(4));
+        assert_eq!(MoveNotation::Coordinate.parse("c3", 3), Some(8));
+        assert_eq!(MoveNotation::Coordinate.parse("o15", 15), Some(224));
+        assert_eq!(MoveNotation::Coordinate.parse("d1", 3), None);
+        assert_eq!(MoveNotation::Coordinate.parse("a0", 3), None);
+        assert_eq!(MoveNotation::Coordinate.parse("4", 3), None);
+    }
+
+    #[test]
+    fn parses_row_column_pairs() {
+        assert_eq!(MoveNotation::RowColumn.parse("1,1", 3), Some(0));
+        assert_eq!(MoveNotation::RowColumn.parse("2, 3", 3), Some(5));
+        assert_eq!(MoveNotation::RowColumn.parse("4,1", 3), None);
+        assert_eq!(MoveNotation::RowColumn.parse("2", 3), None);
+    }
+
+    #[test]
+    fn parses_numpad_keys() {
+        assert_eq!(MoveNotation::Numpad.parse("7", 3), Some(0));
+        assert_eq!(MoveNotation::Numpad.parse("5", 3), Some(4));
+        assert_eq!(MoveNotation::Numpad.parse("3", 3), Some(8));
+        assert_eq!(MoveNotation::Numpad.parse("0", 3), None);
+    }
+
+    #[test]
+    fn index_passes_numbers_through() {
+        assert_eq!(MoveNotation::Index.parse("9", 3), Some(9));
+        assert_eq!(MoveNotation::Index.parse("-1", 3), None);
+    }
+
+    #[test]
+    fn formats_back_to_parsed_space() {
+        for notation in [MoveNotation::Index, MoveNotation::Coordinate, MoveNotation::RowColumn, MoveNotation::Numpad] {
+            for space in 0..9 {
+                assert_eq!(notation.parse(&notation.format(space, 3), 3), Some(space));
+            }
+        }
+        assert_eq!(MoveNotation::Coordinate.format(5, 4), "b2");
+        assert_eq!(MoveNotation::RowColumn.format(5, 4), "2,2");
+    }
+
+    #[test]
+    fn numpad_needs_classic_board() {
+        assert!(MoveNotation::Numpad.supports_size(3));
+        assert!(!MoveNotation::Numpad.supports_size(4));
+        assert!(MoveNotation::Coordinate.supports_size(15));
+    }
+}
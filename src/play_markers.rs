@@ -1,5 +1,16 @@
+// Serialized as "X" or "O".
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PlayMarkers {
     X,
     O,
-}
\ No newline at end of file
+}
+
+impl PlayMarkers {
+    pub fn get_opponent(&self) -> PlayMarkers {
+        match self {
+            PlayMarkers::X => PlayMarkers::O,
+            PlayMarkers::O => PlayMarkers::X,
+        }
+    }
+}
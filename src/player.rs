@@ -0,0 +1,62 @@
+use crate::game::Game;
+use crate::play_markers::PlayMarkers;
+
+pub trait Player {
+    fn choose_move(&mut self, game: &Game) -> u8;
+}
+
+// Plays the game to the end, asking each side's player for their move in turn.
+pub fn play_game(game: &mut Game, x_player: &mut dyn Player, o_player: &mut dyn Player) -> Option<PlayMarkers> {
+    while !game.is_over() {
+        let player: &mut dyn Player = match game.get_active_marker() {
+            PlayMarkers::X => &mut *x_player,
+            PlayMarkers::O => &mut *o_player,
+        };
+        let space = player.choose_move(game);
+        game.play(space).expect("Player picked an unavailable space.");
+    }
+    game.get_winner()
+}
+
+#[cfg(test)]
+mod player_pairing {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::ai::AiPlayer;
+    use crate::ai2::Ai2Player;
+    use crate::game::Game;
+    use crate::human_player::HumanPlayer;
+    use crate::play_markers::PlayMarkers;
+    use crate::player::play_game;
+    use crate::random_player::RandomPlayer;
+
+    #[test]
+    fn ai_engines_draw_each_other() {
+        let mut game = Game::new(3, 3);
+        let winner = play_game(&mut game, &mut AiPlayer::new(StdRng::seed_from_u64(0)), &mut Ai2Player::new(StdRng::seed_from_u64(1)));
+        assert_eq!(winner, None);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn random_player_never_beats_ai() {
+        for seed in 0..5 {
+            let mut game = Game::new(3, 3);
+            let mut random_player = RandomPlayer::new(StdRng::seed_from_u64(seed));
+            let winner = play_game(&mut game, &mut random_player, &mut Ai2Player::new(StdRng::seed_from_u64(seed)));
+            assert_ne!(winner, Some(PlayMarkers::X));
+        }
+    }
+
+    #[test]
+    fn human_players_take_turns() {
+        let mut game = Game::new(3, 3);
+        let mut x_output = Vec::new();
+        let mut o_output = Vec::new();
+        let mut x_player = HumanPlayer::new(&b"0\n1\n2\n"[..], &mut x_output);
+        let mut o_player = HumanPlayer::new(&b"3\n4\n"[..], &mut o_output);
+        let winner = play_game(&mut game, &mut x_player, &mut o_player);
+        assert_eq!(winner, Some(PlayMarkers::X));
+        assert_eq!(game.history(), &[0, 3, 1, 4, 2]);
+    }
+}
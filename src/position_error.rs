@@ -0,0 +1,38 @@
+use std::error::Error;
+use std::fmt;
+use crate::play_markers::PlayMarkers;
+
+// Why a board could not have been reached by X and O taking turns, X first, until one of them won.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PositionError {
+    // The win length and the board size it has to fit.
+    InvalidWinLength(u8, u8),
+    MarkerCount(u8, u8),
+    BothWon,
+    // The marker has a completed line but it is their turn again, so the other side moved after the win.
+    WrongWinner(PlayMarkers),
+    // The marker has completed lines that no single move could have finished together.
+    PlayAfterWin(PlayMarkers),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionError::InvalidWinLength(win_length, size) => {
+                write!(f, "Win length {} must be between 1 and the board size {}.", win_length, size)
+            }
+            PositionError::MarkerCount(x_count, o_count) => {
+                write!(f, "{} X and {} O markers can't be reached by taking turns.", x_count, o_count)
+            }
+            PositionError::BothWon => write!(f, "Both X and O have a completed line."),
+            PositionError::WrongWinner(marker) => {
+                write!(f, "{:?} has a completed line but {:?} moved last.", marker, marker.get_opponent())
+            }
+            PositionError::PlayAfterWin(marker) => {
+                write!(f, "{:?} has completed lines that one move can't finish, so play went on after the win.", marker)
+            }
+        }
+    }
+}
+
+impl Error for PositionError {}
@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fmt;
+use crate::board::{Board, MAX_SIZE};
+use crate::play_markers::PlayMarkers;
+use crate::position_error::PositionError;
+
+// A position written as its rows from top to bottom, then the side to move and, when it differs from the
+// board size, the win length, e.g. "XO_/_X_/__O o" or "X___/_O__/____/____ x 3".
+pub fn format_position(board: &Board, win_length: u8) -> String {
+    let size = board.get_size();
+    let rows: Vec<String> = (0..size).map(|row| {
+        (row * size..(row + 1) * size).map(|space| match board.get_space_marker(&space) {
+            Some(PlayMarkers::X) => 'X',
+            Some(PlayMarkers::O) => 'O',
+            None => '_',
+        }).collect()
+    }).collect();
+    let side = if board.get_played_count().is_multiple_of(2) { 'x' } else { 'o' };
+    let mut position = format!("{} {}", rows.join("/"), side);
+    if win_length != size {
+        position += format!(" {}", win_length).as_str();
+    }
+    position
+}
+
+// Reads the board, side to move and win length. Whether the position could have been reached by play
+// is left to Game::validate.
+pub fn parse_position(s: &str) -> Result<(Board, PlayMarkers, u8), NotationError> {
+    let invalid = || NotationError::InvalidFormat(s.trim().to_string());
+    let mut fields = s.split_whitespace();
+    let rows: Vec<&str> = fields.next().ok_or_else(invalid)?.split('/').collect();
+    let side = fields.next().ok_or_else(invalid)?;
+    let win_length = fields.next();
+    if fields.next().is_some() {
+        return Err(invalid());
+    }
+    if rows.len() > MAX_SIZE as usize {
+        return Err(NotationError::InvalidSize(rows.len()));
+    }
+    let size = rows.len() as u8;
+    let mut board = Board::new(size);
+    for (row, spaces) in rows.iter().enumerate() {
+        if spaces.chars().count() != rows.len() {
+            return Err(NotationError::InvalidRow(row + 1, spaces.to_string()));
+        }
+        for (column, marker) in spaces.chars().enumerate() {
+            let space = (row * rows.len() + column) as u8;
+            match marker {
+                'X' => board.play(space, &PlayMarkers::X),
+                'O' => board.play(space, &PlayMarkers::O),
+                '_' => {}
+                _ => return Err(NotationError::InvalidMarker(marker)),
+            }
+        }
+    }
+    let win_length = match win_length {
+        Some(win_length) => match win_length.parse() {
+            Ok(win_length) if (1..=size).contains(&win_length) => win_length,
+            _ => return Err(NotationError::InvalidWinLength(win_length.to_string())),
+        },
+        None => size,
+    };
+    match side {
+        "x" => Ok((board, PlayMarkers::X, win_length)),
+        "o" => Ok((board, PlayMarkers::O, win_length)),
+        _ => Err(invalid()),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum NotationError {
+    InvalidFormat(String),
+    InvalidSize(usize),
+    InvalidRow(usize, String),
+    InvalidMarker(char),
+    InvalidWinLength(String),
+    InvalidPosition(PositionError),
+    WrongSideToMove(PlayMarkers),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::InvalidFormat(position) => write!(f, "Can't read position \"{}\".", position),
+            NotationError::InvalidSize(rows) => write!(f, "{} rows is not a valid board size.", rows),
+            NotationError::InvalidRow(row, spaces) => write!(f, "Row {} \"{}\" doesn't match the number of rows.", row, spaces),
+            NotationError::InvalidMarker(marker) => write!(f, "Invalid space '{}'.", marker),
+            NotationError::InvalidWinLength(win_length) => write!(f, "Invalid win length \"{}\".", win_length),
+            NotationError::InvalidPosition(error) => write!(f, "{}", error),
+            NotationError::WrongSideToMove(marker) => write!(f, "It is {:?}'s turn to move.", marker),
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+#[cfg(test)]
+mod position_format {
+    use crate::board::Board;
+    use crate::play_markers::PlayMarkers;
+    use crate::position_notation::{format_position, parse_position, NotationError};
+
+    #[test]
+    fn formats_rows_and_side_to_move() {
+        let mut board = Board::new(3);
+        board.play(0, &PlayMarkers::X);
+        board.play(4, &PlayMarkers::O);
+        board.play(8, &PlayMarkers::X);
+        assert_eq!(format_position(&board, 3), "X__/_O_/__X o");
+        assert_eq!(format_position(&Board::new(4), 3), "____/____/____/____ x 3");
+    }
+
+    #[test]
+    fn parses_formatted_position() {
+        let (board, side, win_length) = parse_position("X___/_O__/____/____ x 3").unwrap();
+        assert_eq!(side, PlayMarkers::X);
+        assert_eq!(format_position(&board, win_length), "X___/_O__/____/____ x 3");
+        assert_eq!(parse_position("___/___/___ x").unwrap(), (Board::new(3), PlayMarkers::X, 3));
+    }
+
+    #[test]
+    fn rejects_malformed_positions() {
+        assert_eq!(parse_position("XO_/___/___"), Err(NotationError::InvalidFormat("XO_/___/___".to_string())));
+        assert_eq!(parse_position("XO_/___/___ o 3 3"), Err(NotationError::InvalidFormat("XO_/___/___ o 3 3".to_string())));
+        assert_eq!(parse_position("XO_/___/___ z"), Err(NotationError::InvalidFormat("XO_/___/___ z".to_string())));
+        assert_eq!(parse_position("XO_/__/___ x"), Err(NotationError::InvalidRow(2, "__".to_string())));
+        assert_eq!(parse_position("XZ_/___/___ x"), Err(NotationError::InvalidMarker('Z')));
+        assert_eq!(parse_position("___/___/___ x 4"), Err(NotationError::InvalidWinLength("4".to_string())));
+        assert_eq!(parse_position(&format!("{} x", ["_"; 16].join("/"))), Err(NotationError::InvalidSize(16)));
+    }
+}
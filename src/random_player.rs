@@ -0,0 +1,22 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use crate::game::Game;
+use crate::player::Player;
+
+pub struct RandomPlayer {
+    rng: StdRng,
+}
+
+impl RandomPlayer {
+    pub fn new(rng: StdRng) -> Self {
+        Self {
+            rng,
+        }
+    }
+}
+
+impl Player for RandomPlayer {
+    fn choose_move(&mut self, game: &Game) -> u8 {
+        *game.get_available_plays().choose(&mut self.rng).unwrap()
+    }
+}
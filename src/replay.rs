@@ -0,0 +1,171 @@
+use std::io::{BufRead, Write};
+use crate::cli_game_manager::CLIGameManager;
+use crate::game::Game;
+
+// Steps through a recorded game, using undo and redo to move between positions.
+pub struct ReplayViewer {
+    game: Game,
+    move_count: usize,
+    error: Option<String>,
+}
+
+impl ReplayViewer {
+    // Takes the game at its final position and rewinds it to the start.
+    pub fn new(mut game: Game) -> Self {
+        let move_count = game.history().len();
+        while game.undo().is_some() {}
+        Self {
+            game,
+            move_count,
+            error: None,
+        }
+    }
+
+    pub fn start<R, W>(&mut self, mut reader: R, mut writer: W) where R: BufRead, W: Write {
+        self.print(&mut writer);
+        loop {
+            let mut s = String::new();
+            let read = reader.read_line(&mut s).expect("Unable to read.");
+            if read == 0 || !self.input_command(s.trim()) {
+                break;
+            }
+            self.print(&mut writer);
+        }
+    }
+
+    // Returns false once the viewer should close.
+    pub fn input_command(&mut self, input: &str) -> bool {
+        self.error = None;
+        let (command, argument) = match input.split_once(' ') {
+            Some((command, argument)) => (command, Some(argument.trim())),
+            None => (input, None),
+        };
+        match (command, argument) {
+            ("" | "n" | "next", None) => self.next(),
+            ("b" | "back", None) => self.back(),
+            ("j" | "jump", Some(argument)) => match argument.parse::<usize>() {
+                Ok(move_number) if move_number <= self.move_count => self.jump(move_number),
+                _ => self.error = Some(format!("Error: {} is not a move between 0 and {}.\n", argument, self.move_count)),
+            },
+            ("q" | "quit", None) => return false,
+            _ => self.error = Some(format!("Error: {} is not a command.\n", input)),
+        }
+        true
+    }
+
+    pub fn print<W>(&self, mut writer: W) where W: Write {
+        let mut output = CLIGameManager::format_board_display(&self.game);
+        output += self.position_display().as_str();
+        if let Some(error) = &self.error {
+            output += error.as_str();
+        }
+        output += "Enter n (next), b (back), j <move> (jump) or q (quit): \n";
+        let _result = writeln!(writer, "{}", output);
+    }
+
+    fn next(&mut self) {
+        if self.game.redo().is_none() {
+            self.error = Some("Error: Already at the last move.\n".to_string());
+        }
+    }
+
+    fn back(&mut self) {
+        if self.game.undo().is_none() {
+            self.error = Some("Error: Already at the start.\n".to_string());
+        }
+    }
+
+    fn jump(&mut self, move_number: usize) {
+        while self.game.history().len() > move_number {
+            self.game.undo();
+        }
+        while self.game.history().len() < move_number {
+            self.game.redo();
+        }
+    }
+
+    fn position_display(&self) -> String {
+        let move_number = self.game.history().len();
+        let mut display = match self.game.last_move() {
+            Some(space) => {
+                let marker = self.game.get_active_marker().get_opponent();
+                format!("Move {} of {}: {} played {}.", move_number, self.move_count, CLIGameManager::get_display_marker(&marker), space)
+            }
+            None => format!("Start of game, {} moves recorded.", self.move_count),
+        };
+        if let Some(winner) = self.game.get_winner() {
+            display += format!(" {} Wins!", CLIGameManager::get_display_marker(&winner)).as_str();
+        } else if self.game.is_over() {
+            display += " Game Over!";
+        }
+        display + "\n"
+    }
+}
+
+#[cfg(test)]
+mod replay_viewer {
+    use crate::game::Game;
+    use crate::replay::ReplayViewer;
+
+    const PROMPT: &str = "Enter n (next), b (back), j <move> (jump) or q (quit): \n\n";
+
+    fn replay(moves: &[u8]) -> ReplayViewer {
+        let mut game = Game::new(3, 3);
+        for space in moves {
+            game.play(*space).unwrap();
+        }
+        ReplayViewer::new(game)
+    }
+
+    fn printed(viewer: &ReplayViewer) -> String {
+        let mut output = Vec::new();
+        viewer.print(&mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn starts_at_empty_board() {
+        let viewer = replay(&[0, 3, 1, 4, 2]);
+        assert_eq!(printed(&viewer), format!("_|_|_\n_|_|_\n_|_|_\nStart of game, 5 moves recorded.\n{}", PROMPT));
+    }
+
+    #[test]
+    fn steps_forward_and_back() {
+        let mut viewer = replay(&[0, 3, 1, 4, 2]);
+        viewer.input_command("n");
+        viewer.input_command("next");
+        viewer.input_command("b");
+        assert_eq!(printed(&viewer), format!("X|_|_\n_|_|_\n_|_|_\nMove 1 of 5: X played 0.\n{}", PROMPT));
+    }
+
+    #[test]
+    fn jumps_to_final_move() {
+        let mut viewer = replay(&[0, 3, 1, 4, 2]);
+        viewer.input_command("j 5");
+        assert_eq!(printed(&viewer), format!("X|X|X\no|o|_\n_|_|_\nMove 5 of 5: X played 2. X Wins!\n{}", PROMPT));
+        viewer.input_command("jump 2");
+        assert_eq!(printed(&viewer), format!("X|_|_\nO|_|_\n_|_|_\nMove 2 of 5: O played 3.\n{}", PROMPT));
+    }
+
+    #[test]
+    fn reports_errors() {
+        let mut viewer = replay(&[0, 3]);
+        viewer.input_command("b");
+        assert!(printed(&viewer).ends_with(&format!("Error: Already at the start.\n{}", PROMPT)));
+        viewer.input_command("j 3");
+        assert!(printed(&viewer).ends_with(&format!("Error: 3 is not a move between 0 and 2.\n{}", PROMPT)));
+        viewer.input_command("play");
+        assert!(printed(&viewer).ends_with(&format!("Error: play is not a command.\n{}", PROMPT)));
+    }
+
+    #[test]
+    fn quits_on_command_or_end_of_input() {
+        let mut viewer = replay(&[0, 3]);
+        let mut output = Vec::new();
+        viewer.start(&b"n\nq\nn\n"[..], &mut output);
+        assert_eq!(viewer.game.history(), &[0]);
+        let mut viewer = replay(&[0, 3]);
+        viewer.start(&b"n\nn\n"[..], &mut output);
+        assert_eq!(viewer.game.history(), &[0, 3]);
+    }
+}
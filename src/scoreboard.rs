@@ -0,0 +1,115 @@
+use std::fmt;
+use crate::play_markers::PlayMarkers;
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct PlayerScore {
+    pub wins_as_x: u32,
+    pub wins_as_o: u32,
+    pub draws: u32,
+}
+
+impl PlayerScore {
+    pub fn get_wins(&self) -> u32 {
+        self.wins_as_x + self.wins_as_o
+    }
+}
+
+// Running results of a match between two players who may swap markers from game to game.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Scoreboard {
+    names: [&'static str; 2],
+    scores: [PlayerScore; 2],
+    games: u32,
+}
+
+impl Scoreboard {
+    pub fn new(first_name: &'static str, second_name: &'static str) -> Self {
+        Self {
+            names: [first_name, second_name],
+            scores: [PlayerScore::default(); 2],
+            games: 0,
+        }
+    }
+
+    // Adds one finished game, given the marker the first player had in it.
+    pub fn record(&mut self, first_player: PlayMarkers, winner: Option<PlayMarkers>) {
+        self.games += 1;
+        match winner {
+            Some(winner) => {
+                let score = &mut self.scores[if winner == first_player { 0 } else { 1 }];
+                match winner {
+                    PlayMarkers::X => score.wins_as_x += 1,
+                    PlayMarkers::O => score.wins_as_o += 1,
+                }
+            }
+            None => {
+                for score in &mut self.scores {
+                    score.draws += 1;
+                }
+            }
+        }
+    }
+
+    // True once a player has won more than half of the games in the match.
+    pub fn is_decided(&self, best_of: u32) -> bool {
+        self.scores.iter().any(|score| score.get_wins() * 2 > best_of)
+    }
+
+    pub fn summary(&self) -> String {
+        let (first, second) = (self.scores[0].get_wins(), self.scores[1].get_wins());
+        if first > second {
+            format!("Match over! {} wins the match {}-{}.\n", self.names[0], first, second)
+        } else if second > first {
+            format!("Match over! {} wins the match {}-{}.\n", self.names[1], second, first)
+        } else {
+            format!("Match over! The match is drawn {}-{}.\n", first, second)
+        }
+    }
+}
+
+impl fmt::Display for Scoreboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Score after {} {}:", self.games, if self.games == 1 { "game" } else { "games" })?;
+        for (name, score) in self.names.iter().zip(&self.scores) {
+            writeln!(f, "  {}: {} wins as X, {} wins as O, {} draws", name, score.wins_as_x, score.wins_as_o, score.draws)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod match_scores {
+    use crate::play_markers::PlayMarkers;
+    use crate::scoreboard::{PlayerScore, Scoreboard};
+
+    #[test]
+    fn records_wins_by_marker_and_draws() {
+        let mut scoreboard = Scoreboard::new("You", "AI");
+        scoreboard.record(PlayMarkers::X, Some(PlayMarkers::X));
+        scoreboard.record(PlayMarkers::O, Some(PlayMarkers::X));
+        scoreboard.record(PlayMarkers::X, None);
+        assert_eq!(scoreboard.games, 3);
+        assert_eq!(scoreboard.scores[0], PlayerScore { wins_as_x: 1, wins_as_o: 0, draws: 1 });
+        assert_eq!(scoreboard.scores[1], PlayerScore { wins_as_x: 1, wins_as_o: 0, draws: 1 });
+    }
+
+    #[test]
+    fn is_decided_by_majority() {
+        let mut scoreboard = Scoreboard::new("You", "AI");
+        scoreboard.record(PlayMarkers::O, Some(PlayMarkers::O));
+        assert!(!scoreboard.is_decided(3));
+        scoreboard.record(PlayMarkers::X, Some(PlayMarkers::X));
+        assert!(scoreboard.is_decided(3));
+        assert!(!scoreboard.is_decided(5));
+    }
+
+    #[test]
+    fn prints_scores_and_summary() {
+        let mut scoreboard = Scoreboard::new("Player 1", "Player 2");
+        scoreboard.record(PlayMarkers::X, Some(PlayMarkers::O));
+        assert_eq!(scoreboard.to_string(), "Score after 1 game:\n  Player 1: 0 wins as X, 0 wins as O, 0 draws\n  Player 2: 0 wins as X, 1 wins as O, 0 draws\n");
+        assert_eq!(scoreboard.summary(), "Match over! Player 2 wins the match 1-0.\n");
+        scoreboard.record(PlayMarkers::O, Some(PlayMarkers::O));
+        assert_eq!(scoreboard.summary(), "Match over! The match is drawn 1-1.\n");
+    }
+}
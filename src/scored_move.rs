@@ -0,0 +1,42 @@
+use std::fmt;
+
+// Where a move leads with best play from both sides, counted in moves from now including this one.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MoveResult {
+    Win(u8),
+    Draw,
+    Loss(u8),
+    // The search stopped before the end of the game without finding a forced result.
+    Unclear,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ScoredMove {
+    pub space: u8,
+    pub score: i32,
+    pub result: MoveResult,
+}
+
+impl fmt::Display for MoveResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveResult::Win(moves) => write!(f, "win in {}", moves),
+            MoveResult::Draw => write!(f, "draw"),
+            MoveResult::Loss(moves) => write!(f, "lose in {}", moves),
+            MoveResult::Unclear => write!(f, "unclear"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod move_result {
+    use crate::scored_move::MoveResult;
+
+    #[test]
+    fn prints_result() {
+        assert_eq!(MoveResult::Win(1).to_string(), "win in 1");
+        assert_eq!(MoveResult::Draw.to_string(), "draw");
+        assert_eq!(MoveResult::Loss(3).to_string(), "lose in 3");
+        assert_eq!(MoveResult::Unclear.to_string(), "unclear");
+    }
+}
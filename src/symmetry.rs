@@ -0,0 +1,111 @@
+use crate::board::Board;
+
+// One of the eight rotations and reflections of a square board. Rotations are clockwise.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Symmetry {
+    Identity,
+    RotateQuarter,
+    RotateHalf,
+    RotateThreeQuarters,
+    // Mirror left to right
+    FlipHorizontal,
+    // Mirror top to bottom
+    FlipVertical,
+    // Mirror across the top left to bottom right diagonal
+    FlipDiagonal,
+    // Mirror across the top right to bottom left diagonal
+    FlipAntiDiagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::RotateQuarter,
+        Symmetry::RotateHalf,
+        Symmetry::RotateThreeQuarters,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    // Where a space of a board with the given size ends up after the symmetry.
+    pub fn map_space(&self, space: u8, size: u8) -> u8 {
+        let (row, column) = (space / size, space % size);
+        let last = size - 1;
+        let (row, column) = match self {
+            Symmetry::Identity => (row, column),
+            Symmetry::RotateQuarter => (column, last - row),
+            Symmetry::RotateHalf => (last - row, last - column),
+            Symmetry::RotateThreeQuarters => (last - column, row),
+            Symmetry::FlipHorizontal => (row, last - column),
+            Symmetry::FlipVertical => (last - row, column),
+            Symmetry::FlipDiagonal => (column, row),
+            Symmetry::FlipAntiDiagonal => (last - column, last - row),
+        };
+        row * size + column
+    }
+
+    // The symmetry that undoes this one.
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::RotateQuarter => Symmetry::RotateThreeQuarters,
+            Symmetry::RotateThreeQuarters => Symmetry::RotateQuarter,
+            _ => *self,
+        }
+    }
+
+    pub fn get_mapping(&self, size: u8) -> Vec<u8> {
+        (0..size * size).map(|space| self.map_space(space, size)).collect()
+    }
+}
+
+// A board after a symmetry, where mapping[space] is the space of the transformed board that the original
+// space moved to. Moves found on the transformed board map back with to_original.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SymmetricBoard {
+    pub symmetry: Symmetry,
+    pub board: Board,
+    pub mapping: Vec<u8>,
+}
+
+impl SymmetricBoard {
+    pub fn to_original(&self, space: u8) -> u8 {
+        self.symmetry.inverse().map_space(space, self.board.get_size())
+    }
+}
+
+#[cfg(test)]
+mod board_symmetries {
+    use crate::symmetry::Symmetry;
+
+    #[test]
+    fn maps_corner_and_edge_of_classic_board() {
+        let corners: Vec<u8> = Symmetry::ALL.iter().map(|symmetry| symmetry.map_space(0, 3)).collect();
+        assert_eq!(corners, vec![0, 2, 8, 6, 2, 6, 0, 8]);
+        let edges: Vec<u8> = Symmetry::ALL.iter().map(|symmetry| symmetry.map_space(1, 3)).collect();
+        assert_eq!(edges, vec![1, 5, 7, 3, 1, 7, 3, 5]);
+    }
+
+    #[test]
+    fn mappings_are_permutations() {
+        for size in 1..=5 {
+            for symmetry in Symmetry::ALL {
+                let mut mapping = symmetry.get_mapping(size);
+                mapping.sort();
+                assert_eq!(mapping, (0..size * size).collect::<Vec<u8>>());
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_symmetry() {
+        for size in [3, 4] {
+            for symmetry in Symmetry::ALL {
+                for space in 0..size * size {
+                    assert_eq!(symmetry.inverse().map_space(symmetry.map_space(space, size), size), space);
+                }
+            }
+        }
+    }
+}
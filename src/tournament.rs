@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use clap::ArgEnum;
+use crate::ai::{self, AiPlayer};
+use crate::ai2::Ai2Player;
+use crate::difficulty::Difficulty;
+use crate::game::Game;
+use crate::play_markers::PlayMarkers;
+use crate::player::{play_game, Player};
+use crate::random_player::RandomPlayer;
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Engine {
+    Ai,
+    Ai2,
+    Ai2Medium,
+    Random,
+}
+
+impl Engine {
+    pub fn get_player(&self, rng: StdRng) -> Box<dyn Player> {
+        match self {
+            Engine::Ai => Box::new(AiPlayer::new(rng)),
+            Engine::Ai2 => Box::new(Ai2Player::new(rng)),
+            Engine::Ai2Medium => Difficulty::Medium.get_player(rng),
+            Engine::Random => Box::new(RandomPlayer::new(rng)),
+        }
+    }
+
+    // Only the exhaustive ai is limited; the others search to a fixed depth on large boards.
+    pub fn supports_size(&self, size: u8) -> bool {
+        *self != Engine::Ai || ai::supports_size(size)
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        self.to_possible_value().unwrap().get_name()
+    }
+}
+
+// Results from the first engine's point of view.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Tally {
+    pub first_wins: u32,
+    pub draws: u32,
+    pub second_wins: u32,
+}
+
+impl Tally {
+    fn add(&mut self, first_won: Option<bool>) {
+        match first_won {
+            Some(true) => self.first_wins += 1,
+            Some(false) => self.second_wins += 1,
+            None => self.draws += 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct TournamentStats {
+    pub games: u32,
+    pub total_moves: u32,
+    pub results: Tally,
+    // Results keyed by the first space played in each game.
+    pub openings: BTreeMap<u8, Tally>,
+}
+
+impl TournamentStats {
+    pub fn get_average_length(&self) -> f64 {
+        if self.games == 0 { 0.0 } else { self.total_moves as f64 / self.games as f64 }
+    }
+
+    pub fn report(&self, first: Engine, second: Engine) -> String {
+        let mut report = format!("{} vs {}, {} games\n", first.get_name(), second.get_name(), self.games);
+        report += tally_display(&self.results, first, second).as_str();
+        report += format!("Average game length: {:.1} moves\n", self.get_average_length()).as_str();
+        report += "Openings:\n";
+        for (space, tally) in &self.openings {
+            report += format!("  {}: {}", space, tally_display(tally, first, second)).as_str();
+        }
+        report
+    }
+}
+
+fn tally_display(tally: &Tally, first: Engine, second: Engine) -> String {
+    format!("{} wins: {}, draws: {}, {} wins: {}\n", first.get_name(), tally.first_wins, tally.draws, second.get_name(), tally.second_wins)
+}
+
+// Plays the given number of games on fresh boards, with the engines taking turns to move first.
+pub fn run(first: Engine, second: Engine, games: u32, size: u8, win_length: u8, rng: &mut StdRng) -> TournamentStats {
+    let mut first_player = first.get_player(StdRng::seed_from_u64(rng.gen()));
+    let mut second_player = second.get_player(StdRng::seed_from_u64(rng.gen()));
+    let mut stats = TournamentStats::default();
+    for round in 0..games {
+        let mut game = Game::new(size, win_length);
+        let first_plays_x = round % 2 == 0;
+        let winner = if first_plays_x {
+            play_game(&mut game, first_player.as_mut(), second_player.as_mut())
+        } else {
+            play_game(&mut game, second_player.as_mut(), first_player.as_mut())
+        };
+        let first_won = winner.map(|marker| (marker == PlayMarkers::X) == first_plays_x);
+        stats.games += 1;
+        stats.total_moves += game.history().len() as u32;
+        stats.results.add(first_won);
+        if let Some(opening) = game.history().first() {
+            stats.openings.entry(*opening).or_default().add(first_won);
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tournament_runner {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::tournament::{run, Engine, Tally};
+
+    #[test]
+    fn perfect_engines_always_draw() {
+        let stats = run(Engine::Ai, Engine::Ai2, 4, 3, 3, &mut StdRng::seed_from_u64(0));
+        assert_eq!(stats.results, Tally { first_wins: 0, draws: 4, second_wins: 0 });
+        assert_eq!(stats.games, 4);
+        assert_eq!(stats.total_moves, 36);
+        assert_eq!(stats.get_average_length(), 9.0);
+    }
+
+    #[test]
+    fn exhaustive_ai_needs_classic_board() {
+        assert!(Engine::Ai.supports_size(3));
+        assert!(!Engine::Ai.supports_size(4));
+        assert!(Engine::Ai2.supports_size(15));
+        assert!(Engine::Random.supports_size(4));
+    }
+
+    #[test]
+    fn random_engine_never_beats_ai2() {
+        let stats = run(Engine::Ai2, Engine::Random, 10, 3, 3, &mut StdRng::seed_from_u64(1));
+        assert_eq!(stats.results.second_wins, 0);
+        let opening_games: u32 = stats.openings.values().map(|tally| tally.first_wins + tally.draws + tally.second_wins).sum();
+        assert_eq!(opening_games, 10);
+    }
+
+    #[test]
+    fn same_seed_gives_same_stats() {
+        let first = run(Engine::Random, Engine::Ai2Medium, 6, 3, 3, &mut StdRng::seed_from_u64(5));
+        let second = run(Engine::Random, Engine::Ai2Medium, 6, 3, 3, &mut StdRng::seed_from_u64(5));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn reports_results() {
+        let stats = run(Engine::Ai2, Engine::Ai2, 2, 3, 3, &mut StdRng::seed_from_u64(0));
+        let report = stats.report(Engine::Ai2, Engine::Ai2);
+        assert!(report.starts_with("ai2 vs ai2, 2 games\nai2 wins: 0, draws: 2, ai2 wins: 0\nAverage game length: 9.0 moves\nOpenings:\n"));
+    }
+}
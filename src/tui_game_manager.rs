@@ -0,0 +1,254 @@
+use std::io::{self, Write};
+use crossterm::{cursor, execute, queue, terminal};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
+use rand::rngs::StdRng;
+use crate::Game;
+use crate::cli_game_manager::{CLIGameManager, GameMode};
+use crate::difficulty::Difficulty;
+use crate::move_outcome::MoveOutcome;
+use crate::player::Player;
+use crate::play_markers::PlayMarkers;
+
+const HELP_LINE: &str = "Arrows or hjkl move, Enter places, q quits.";
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TuiAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Place,
+    Quit,
+}
+
+impl TuiAction {
+    // Raw mode stops the terminal turning Ctrl-C into an interrupt, so it arrives as a key and quits here.
+    pub fn from_key(key: KeyEvent) -> Option<Self> {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Some(TuiAction::Quit);
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => Some(TuiAction::Up),
+            KeyCode::Down | KeyCode::Char('j') => Some(TuiAction::Down),
+            KeyCode::Left | KeyCode::Char('h') => Some(TuiAction::Left),
+            KeyCode::Right | KeyCode::Char('l') => Some(TuiAction::Right),
+            KeyCode::Enter | KeyCode::Char(' ') => Some(TuiAction::Place),
+            KeyCode::Esc | KeyCode::Char('q') => Some(TuiAction::Quit),
+            _ => None,
+        }
+    }
+}
+
+// Full screen game in raw terminal mode, moving a cursor over the board instead of typing spaces.
+pub struct TuiGameManager {
+    game: Game,
+    mode: GameMode,
+    ai: Box<dyn Player>,
+    cursor: u8,
+    error: Option<String>,
+    quit: bool,
+}
+
+impl TuiGameManager {
+    pub fn new(mode: GameMode, game: Game, difficulty: Difficulty, rng: StdRng) -> Self {
+        let cursor = game.get_board().get_space_count() / 2;
+        Self {
+            game,
+            mode,
+            ai: difficulty.get_player(rng),
+            cursor,
+            error: None,
+            quit: false,
+        }
+    }
+
+    // Restores the terminal before returning, even when drawing or reading fails.
+    pub fn start<W>(&mut self, mut writer: W) -> io::Result<()> where W: Write {
+        terminal::enable_raw_mode()?;
+        execute!(writer, terminal::EnterAlternateScreen, cursor::Hide)?;
+        let result = self.run(&mut writer);
+        execute!(writer, cursor::Show, terminal::LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+
+    fn run<W>(&mut self, writer: &mut W) -> io::Result<()> where W: Write {
+        if self.is_ai_turn() {
+            self.ai_play();
+        }
+        while !self.quit {
+            self.draw(&mut *writer)?;
+            if let Event::Key(key @ KeyEvent { kind: KeyEventKind::Press, .. }) = event::read()? {
+                if let Some(action) = TuiAction::from_key(key) {
+                    self.handle_action(action);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_action(&mut self, action: TuiAction) {
+        let size = self.game.get_board().get_size();
+        let (row, column) = (self.cursor / size, self.cursor % size);
+        match action {
+            TuiAction::Up => self.cursor = row.saturating_sub(1) * size + column,
+            TuiAction::Down => self.cursor = (row + 1).min(size - 1) * size + column,
+            TuiAction::Left => self.cursor = row * size + column.saturating_sub(1),
+            TuiAction::Right => self.cursor = row * size + (column + 1).min(size - 1),
+            TuiAction::Place => self.place(),
+            TuiAction::Quit => self.quit = true,
+        }
+    }
+
+    fn place(&mut self) {
+        if self.game.is_over() {
+            return;
+        }
+        match self.game.play(self.cursor) {
+            Ok(outcome) => {
+                self.error = None;
+                if self.mode != GameMode::Local && outcome == MoveOutcome::Continue {
+                    self.ai_play();
+                }
+            }
+            Err(error) => self.error = Some(error.to_string()),
+        }
+    }
+
+    pub fn draw<W>(&self, mut writer: W) -> io::Result<()> where W: Write {
+        queue!(writer, terminal::Clear(terminal::ClearType::All))?;
+        let size = self.game.get_board().get_size();
+        for row in 0..size {
+            queue!(writer, cursor::MoveTo(0, row as u16))?;
+            for column in 0..size {
+                let space = row * size + column;
+                let (text, color) = match self.game.get_board().get_space_marker(&space) {
+                    Some(marker) => (CLIGameManager::get_display_marker(marker), get_marker_color(marker)),
+                    None => ("·", Color::DarkGrey),
+                };
+                if self.game.is_in_completed_line(space) {
+                    queue!(writer, SetBackgroundColor(Color::DarkGreen), SetAttribute(Attribute::Bold))?;
+                }
+                if space == self.cursor && !self.game.is_over() {
+                    queue!(writer, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(writer, SetForegroundColor(color), Print(format!(" {} ", text)), SetAttribute(Attribute::Reset), ResetColor)?;
+            }
+        }
+        let status_row = size as u16 + 1;
+        queue!(writer, cursor::MoveTo(0, status_row), Print(self.status_line()))?;
+        if let Some(error) = &self.error {
+            queue!(writer, cursor::MoveTo(0, status_row + 1), SetForegroundColor(Color::Red), Print(error), ResetColor)?;
+        }
+        queue!(writer, cursor::MoveTo(0, status_row + 2), Print(HELP_LINE))?;
+        writer.flush()
+    }
+
+    fn status_line(&self) -> String {
+        if let Some(winner) = self.game.get_winner() {
+            format!("{} Wins!", CLIGameManager::get_display_marker(&winner))
+        } else if self.game.is_over() {
+            "Game Over!".to_string()
+        } else {
+            format!("{}'s turn!", CLIGameManager::get_display_marker(&self.game.get_active_marker()))
+        }
+    }
+
+    fn is_ai_turn(&self) -> bool {
+        !self.game.is_over() && self.mode.is_ai(&self.game.get_active_marker())
+    }
+
+    fn ai_play(&mut self) {
+        let space = self.ai.choose_move(&self.game);
+        self.game.play(space).expect("AI picked an unavailable space.");
+    }
+}
+
+fn get_marker_color(marker: &PlayMarkers) -> Color {
+    match marker {
+        PlayMarkers::X => Color::Cyan,
+        PlayMarkers::O => Color::Yellow,
+    }
+}
+
+#[cfg(test)]
+mod tui_game {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::cli_game_manager::GameMode;
+    use crate::difficulty::Difficulty;
+    use crate::game::Game;
+    use crate::tui_game_manager::{TuiAction, TuiGameManager};
+
+    fn local_game() -> TuiGameManager {
+        TuiGameManager::new(GameMode::Local, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0))
+    }
+
+    fn drawn(tui: &TuiGameManager) -> String {
+        let mut output = Vec::new();
+        tui.draw(&mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn maps_arrow_and_vim_keys() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(TuiAction::from_key(key(KeyCode::Up)), Some(TuiAction::Up));
+        assert_eq!(TuiAction::from_key(key(KeyCode::Char('h'))), Some(TuiAction::Left));
+        assert_eq!(TuiAction::from_key(key(KeyCode::Enter)), Some(TuiAction::Place));
+        assert_eq!(TuiAction::from_key(key(KeyCode::Char('q'))), Some(TuiAction::Quit));
+        assert_eq!(TuiAction::from_key(key(KeyCode::Char('x'))), None);
+        assert_eq!(TuiAction::from_key(key(KeyCode::Char('c'))), None);
+        assert_eq!(TuiAction::from_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(TuiAction::Quit));
+    }
+
+    #[test]
+    fn cursor_starts_in_center_and_stops_at_edges() {
+        let mut tui = local_game();
+        assert_eq!(tui.cursor, 4);
+        for action in [TuiAction::Up, TuiAction::Up, TuiAction::Left, TuiAction::Left] {
+            tui.handle_action(action);
+        }
+        assert_eq!(tui.cursor, 0);
+        for action in [TuiAction::Down, TuiAction::Down, TuiAction::Down, TuiAction::Right] {
+            tui.handle_action(action);
+        }
+        assert_eq!(tui.cursor, 7);
+    }
+
+    #[test]
+    fn places_marker_under_cursor() {
+        let mut tui = local_game();
+        tui.handle_action(TuiAction::Place);
+        tui.handle_action(TuiAction::Place);
+        assert_eq!(tui.game.history(), &[4]);
+        assert_eq!(tui.error, Some("Can't play in position 4, as it has been already played.".to_string()));
+        tui.handle_action(TuiAction::Right);
+        tui.handle_action(TuiAction::Place);
+        assert_eq!(tui.game.history(), &[4, 5]);
+        assert_eq!(tui.error, None);
+    }
+
+    #[test]
+    fn ai_answers_placed_marker() {
+        let mut tui = TuiGameManager::new(GameMode::AiLast, Game::new(3, 3), Difficulty::Hard, StdRng::seed_from_u64(0));
+        tui.handle_action(TuiAction::Place);
+        assert_eq!(tui.game.history().len(), 2);
+        assert!(drawn(&tui).contains("X's turn!"));
+    }
+
+    #[test]
+    fn highlights_winning_line() {
+        let mut tui = local_game();
+        for space in [0, 3, 1, 4, 2] {
+            tui.game.play(space).unwrap();
+        }
+        let output = drawn(&tui);
+        assert_eq!(output.matches("\x1b[48;5;2m").count(), 3);
+        assert!(output.contains("X Wins!"));
+        tui.handle_action(TuiAction::Quit);
+        assert!(tui.quit);
+    }
+}
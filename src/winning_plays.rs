@@ -1,23 +1,75 @@
-use std::collections::HashMap;
-
-pub fn get_winning_plays() -> HashMap<u8, Vec<[u8; 3]>> {
-    const TOP_HORIZONTAL: [u8; 3] = [0, 1, 2];
-    const MIDDLE_HORIZONTAL: [u8; 3] = [3, 4, 5];
-    const BOTTOM_HORIZONTAL: [u8; 3] = [6, 7, 8];
-    const LEFT_VERTICAL: [u8; 3] = [0, 3, 6];
-    const MIDDLE_VERTICAL: [u8; 3] = [1, 4, 7];
-    const RIGHT_VERTICAL: [u8; 3] = [2, 5, 8];
-    const DIAGONAL_DOWN: [u8; 3] = [0, 4, 8];
-    const DIAGONAL_UP: [u8; 3] = [2, 4, 6];
-    let mut winning_plays = HashMap::new();
-    winning_plays.insert(0, vec![TOP_HORIZONTAL, DIAGONAL_DOWN, LEFT_VERTICAL]);
-    winning_plays.insert(1, vec![TOP_HORIZONTAL, MIDDLE_VERTICAL]);
-    winning_plays.insert(2, vec![TOP_HORIZONTAL, RIGHT_VERTICAL, DIAGONAL_UP]);
-    winning_plays.insert(3, vec![LEFT_VERTICAL, MIDDLE_HORIZONTAL]);
-    winning_plays.insert(4, vec![MIDDLE_VERTICAL, MIDDLE_HORIZONTAL, DIAGONAL_UP, DIAGONAL_DOWN]);
-    winning_plays.insert(5, vec![MIDDLE_HORIZONTAL, RIGHT_VERTICAL]);
-    winning_plays.insert(6, vec![BOTTOM_HORIZONTAL, LEFT_VERTICAL, DIAGONAL_UP]);
-    winning_plays.insert(7, vec![BOTTOM_HORIZONTAL, MIDDLE_VERTICAL]);
-    winning_plays.insert(8, vec![BOTTOM_HORIZONTAL, RIGHT_VERTICAL, DIAGONAL_DOWN]);
+use crate::bit_mask::BitMask;
+
+// Row and column steps for horizontal, vertical, diagonal down and diagonal up lines.
+const DIRECTIONS: [(i16, i16); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+// Winning line masks for every space, indexed by space.
+pub fn get_winning_plays(size: u8, win_length: u8) -> Vec<Vec<BitMask>> {
+    let mut winning_plays = vec![Vec::new(); size as usize * size as usize];
+    for line in get_winning_lines(size, win_length) {
+        let mask = BitMask::from_spaces(&line);
+        for space in line {
+            winning_plays[space as usize].push(mask);
+        }
+    }
     winning_plays
 }
+
+pub fn get_winning_line_masks(size: u8, win_length: u8) -> Vec<BitMask> {
+    get_winning_lines(size, win_length).iter().map(|line| BitMask::from_spaces(line)).collect()
+}
+
+pub fn get_winning_lines(size: u8, win_length: u8) -> Vec<Vec<u8>> {
+    let size = size as i16;
+    let length = win_length as i16;
+    let mut lines = Vec::new();
+    for row in 0..size {
+        for column in 0..size {
+            for (row_step, column_step) in DIRECTIONS {
+                let last_row = row + row_step * (length - 1);
+                let last_column = column + column_step * (length - 1);
+                if last_row >= size || last_column < 0 || last_column >= size {
+                    continue;
+                }
+                let line = (0..length)
+                    .map(|i| ((row + row_step * i) * size + column + column_step * i) as u8)
+                    .collect();
+                lines.push(line);
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod winning_lines {
+    use crate::winning_plays::{get_winning_lines, get_winning_plays};
+
+    #[test]
+    fn classic_board_has_eight_lines() {
+        let mut lines = get_winning_lines(3, 3);
+        lines.sort();
+        assert_eq!(lines, vec![
+            vec![0, 1, 2], vec![0, 3, 6], vec![0, 4, 8], vec![1, 4, 7],
+            vec![2, 4, 6], vec![2, 5, 8], vec![3, 4, 5], vec![6, 7, 8],
+        ]);
+    }
+
+    #[test]
+    fn center_belongs_to_four_lines() {
+        assert_eq!(get_winning_plays(3, 3)[4].len(), 4);
+    }
+
+    #[test]
+    fn four_by_four_three_in_a_row() {
+        // 8 horizontal, 8 vertical and 4 in each diagonal direction.
+        assert_eq!(get_winning_lines(4, 3).len(), 24);
+    }
+
+    #[test]
+    fn gomoku_board_line_count() {
+        // 11 starting positions per row for each of 15 rows, in both straight directions,
+        // plus 11 * 11 in each diagonal direction.
+        assert_eq!(get_winning_lines(15, 5).len(), 2 * 15 * 11 + 2 * 11 * 11);
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::OnceLock;
+use crate::board::{Board, MAX_SIZE};
+use crate::play_markers::PlayMarkers;
+
+const SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+static KEYS: OnceLock<Vec<[u64; 2]>> = OnceLock::new();
+
+// Random key for a marker in a space, fixed across runs so hashes are reproducible.
+pub fn get_key(space: u8, marker: &PlayMarkers) -> u64 {
+    let keys = KEYS.get_or_init(generate_keys);
+    match marker {
+        PlayMarkers::X => keys[space as usize][0],
+        PlayMarkers::O => keys[space as usize][1],
+    }
+}
+
+pub fn hash_board(board: &Board) -> u64 {
+    let mut hash = 0;
+    for space in 0..board.get_space_count() {
+        if let Some(marker) = board.get_space_marker(&space) {
+            hash ^= get_key(space, marker);
+        }
+    }
+    hash
+}
+
+fn generate_keys() -> Vec<[u64; 2]> {
+    let mut state = SEED;
+    let space_count = MAX_SIZE as usize * MAX_SIZE as usize;
+    (0..space_count).map(|_| [split_mix(&mut state), split_mix(&mut state)]).collect()
+}
+
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod hashing {
+    use crate::board::Board;
+    use crate::play_markers::PlayMarkers;
+    use crate::zobrist::{get_key, hash_board};
+
+    #[test]
+    fn hash_does_not_depend_on_move_order() {
+        let mut board = Board::new(3);
+        board.play(0, &PlayMarkers::X);
+        board.play(4, &PlayMarkers::O);
+        let mut other = Board::new(3);
+        other.play(4, &PlayMarkers::O);
+        other.play(0, &PlayMarkers::X);
+        assert_eq!(hash_board(&board), hash_board(&other));
+        assert_eq!(hash_board(&board), get_key(0, &PlayMarkers::X) ^ get_key(4, &PlayMarkers::O));
+    }
+
+    #[test]
+    fn markers_have_different_keys() {
+        assert_ne!(get_key(4, &PlayMarkers::X), get_key(4, &PlayMarkers::O));
+    }
+}